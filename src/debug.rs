@@ -0,0 +1,82 @@
+//! # Debugging Helpers
+//!
+//! Tools for tracking down misbehaving game implementations.
+//!
+//! When strict mode is on, the engine reports inconsistencies it runs into during
+//! search (such as a non-terminal position without legal moves) together with the
+//! fingerprints of every position on the way there. Replaying the moves from the
+//! search root and comparing fingerprints pins down where the game went wrong.
+
+use crate::GameState;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static STRICT: AtomicBool = AtomicBool::new(false);
+
+/// Enables or disables reporting of inconsistencies found during search
+///
+/// Off by default. Strict mode makes the search record the moves of each
+/// simulation, so it is slower.
+pub fn set_strict(enabled: bool) {
+    STRICT.store(enabled, Ordering::Relaxed);
+}
+
+/// Returns whether strict mode is on
+pub fn is_strict() -> bool {
+    STRICT.load(Ordering::Relaxed)
+}
+
+/// Hashes a board and the player to move
+///
+/// This is the default `GameState::state_fingerprint`. It uses 64-bit FNV-1a over
+/// the board dimensions and cells, so fingerprints are stable across runs and
+/// Rust releases and can be compared between logs.
+pub fn board_fingerprint(board: &[Vec<i32>], current_player: i32) -> u64 {
+    const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let mut hash = FNV_OFFSET;
+    let mut feed = |value: i32| {
+        for byte in value.to_le_bytes() {
+            hash = (hash ^ byte as u64).wrapping_mul(FNV_PRIME);
+        }
+    };
+    feed(board.len() as i32);
+    for row in board {
+        feed(row.len() as i32);
+        row.iter().for_each(|&cell| feed(cell));
+    }
+    feed(current_player);
+    hash
+}
+
+/// Returns the fingerprint of `start` and of each position reached by playing `moves`
+pub fn fingerprint_path<S: GameState>(start: &S, moves: &[S::Move]) -> Vec<u64> {
+    let mut state = start.clone();
+    let mut path = Vec::with_capacity(moves.len() + 1);
+    path.push(state.state_fingerprint());
+    for mv in moves {
+        state.make_move(mv);
+        path.push(state.state_fingerprint());
+    }
+    path
+}
+
+/// Reports an inconsistency found after playing `moves` from `start`
+///
+/// In strict mode the reason, the moves and the fingerprint path are written to
+/// stderr. The path is returned either way.
+///
+/// # Arguments
+/// * `start` - Position the moves are played from, usually the search root
+/// * `moves` - Moves leading to the inconsistent position
+/// * `reason` - What went wrong
+pub fn record_divergence<S: GameState>(start: &S, moves: &[S::Move], reason: &str) -> Vec<u64> {
+    let path = fingerprint_path(start, moves);
+    if is_strict() {
+        let fingerprints: Vec<String> = path.iter().map(|f| format!("{:016x}", f)).collect();
+        eprintln!("[MCTS] Divergence: {}", reason);
+        eprintln!("[MCTS]   moves: {:?}", moves);
+        eprintln!("[MCTS]   fingerprints: {}", fingerprints.join(" -> "));
+    }
+    path
+}
@@ -69,6 +69,8 @@ pub enum MoveValidationError {
     MismatchedGameType,
     /// The game is already in a terminal state
     GameAlreadyOver,
+    /// The controller is reviewing an earlier position and the board is read-only
+    Reviewing,
     /// Custom validation error with message
     Custom(String),
 }
@@ -79,6 +81,7 @@ impl std::fmt::Display for MoveValidationError {
             MoveValidationError::IllegalMove => write!(f, "Illegal move"),
             MoveValidationError::MismatchedGameType => write!(f, "Move type doesn't match game"),
             MoveValidationError::GameAlreadyOver => write!(f, "Game is already over"),
+            MoveValidationError::Reviewing => write!(f, "Cannot move while reviewing the game"),
             MoveValidationError::Custom(msg) => write!(f, "{}", msg),
         }
     }
@@ -159,19 +162,25 @@ impl GameStatus {
 pub struct GameController {
     /// The authoritative game state
     game_state: GameWrapper,
+    /// The state the game started from, used to replay history
+    initial_state: GameWrapper,
     /// Complete history of moves made
     move_history: Vec<MoveHistoryEntry>,
     /// Current game status
     status: GameStatus,
+    /// Number of moves replayed while reviewing (None when at the live position)
+    review_index: Option<usize>,
 }
 
 impl GameController {
     /// Create a new game controller with the given initial state
     pub fn new(initial_state: GameWrapper) -> Self {
         Self {
-            game_state: initial_state,
+            game_state: initial_state.clone(),
+            initial_state,
             move_history: Vec::new(),
             status: GameStatus::InProgress,
+            review_index: None,
         }
     }
 
@@ -179,6 +188,11 @@ impl GameController {
     ///
     /// Returns Ok(()) if the move is valid, or an error describing why it's invalid.
     pub fn validate_move(&self, mv: &MoveWrapper) -> Result<(), MoveValidationError> {
+        // The board is read-only while reviewing earlier positions
+        if self.is_reviewing() {
+            return Err(MoveValidationError::Reviewing);
+        }
+
         // Check if game is already over
         if self.status.is_game_over() {
             return Err(MoveValidationError::GameAlreadyOver);
@@ -240,6 +254,11 @@ impl GameController {
     /// Use with caution - this bypasses validation. Should only be used
     /// for moves that come from the AI search which uses the same game rules.
     pub fn apply_trusted_move(&mut self, mv: MoveWrapper) -> MoveResult {
+        if self.is_reviewing() {
+            return MoveResult::Invalid {
+                reason: MoveValidationError::Reviewing,
+            };
+        }
         if self.status.is_game_over() {
             return MoveResult::GameOver;
         }
@@ -342,9 +361,40 @@ impl GameController {
 
     /// Reset the game to its initial state
     pub fn reset(&mut self, new_state: GameWrapper) {
-        self.game_state = new_state;
+        self.game_state = new_state.clone();
+        self.initial_state = new_state;
         self.move_history.clear();
         self.status = GameStatus::InProgress;
+        self.review_index = None;
+    }
+
+    /// Show the position after the first `index` recorded moves
+    ///
+    /// The state is rebuilt by replaying the move history from the initial position.
+    /// While an earlier position is shown the board is read-only and the AI should
+    /// not search. Jumping to the last move (or beyond) returns to the live position.
+    pub fn goto_move(&mut self, index: usize) {
+        let index = index.min(self.move_history.len());
+        let mut state = self.initial_state.clone();
+        for entry in &self.move_history[..index] {
+            state.make_move(&entry.move_made);
+        }
+        self.game_state = state;
+        self.review_index = if index < self.move_history.len() {
+            Some(index)
+        } else {
+            None
+        };
+    }
+
+    /// Check if an earlier position is being reviewed
+    pub fn is_reviewing(&self) -> bool {
+        self.review_index.is_some()
+    }
+
+    /// Get the number of moves shown while reviewing (None at the live position)
+    pub fn review_index(&self) -> Option<usize> {
+        self.review_index
     }
 
     /// Format move history as a string suitable for copying to clipboard
@@ -482,6 +532,36 @@ mod tests {
         assert!(matches!(controller.status, GameStatus::InProgress));
     }
 
+    #[test]
+    fn test_goto_move() {
+        let initial = GameWrapper::Gomoku(GomokuState::new(15, 5));
+        let mut controller = GameController::new(initial.clone());
+        let moves = [(7, 7), (7, 8), (8, 8)];
+        let mut states = vec![initial.get_board().clone()];
+        for (r, c) in moves {
+            controller.try_make_move(MoveWrapper::Gomoku(crate::games::gomoku::GomokuMove(r, c)));
+            states.push(controller.get_board().clone());
+        }
+
+        controller.goto_move(0);
+        assert!(controller.is_reviewing());
+        assert_eq!(controller.get_board(), initial.get_board());
+
+        // The board is read-only while reviewing
+        let mv = MoveWrapper::Gomoku(crate::games::gomoku::GomokuMove(0, 0));
+        assert!(matches!(
+            controller.try_make_move(mv),
+            MoveResult::Invalid { reason: MoveValidationError::Reviewing }
+        ));
+
+        for (n, board) in states.iter().enumerate() {
+            controller.goto_move(n);
+            assert_eq!(controller.get_board(), board);
+        }
+        assert!(!controller.is_reviewing());
+        assert_eq!(controller.move_count(), 3);
+    }
+
     #[test]
     fn test_format_history() {
         let state = GameWrapper::Gomoku(GomokuState::new(15, 5));
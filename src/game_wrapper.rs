@@ -46,7 +46,7 @@ use crate::games::connect4::{Connect4Move, Connect4State}; // Gravity-based 4-in
 use crate::games::gomoku::{GomokuMove, GomokuState}; // Classic 5-in-a-row game
 use crate::games::hive::{HiveMove, HiveState}; // Hex-based insect strategy game
 use crate::games::othello::{OthelloMove, OthelloState}; // Reversi/Othello territory game
-use mcts::GameState; // Core trait for MCTS compatibility
+use mcts::{GamePhase, GameState, InputKind, MoveEffects}; // Core trait for MCTS compatibility
 use std::fmt; // Formatting traits for display
 
 /// Wrapper enum for all supported game types
@@ -230,6 +230,22 @@ macro_rules! impl_game_dispatch {
                 }
             }
 
+            fn get_canonical_moves(&self) -> Option<Vec<Self::Move>> {
+                match self {
+                    $(GameWrapper::$variant(g) => g
+                        .get_canonical_moves()
+                        .map(|moves| moves.into_iter().map(MoveWrapper::$variant).collect()),)*
+                }
+            }
+
+            fn get_tactical_moves(&self) -> Option<Vec<Self::Move>> {
+                match self {
+                    $(GameWrapper::$variant(g) => g
+                        .get_tactical_moves()
+                        .map(|moves| moves.into_iter().map(MoveWrapper::$variant).collect()),)*
+                }
+            }
+
             fn make_move(&mut self, mv: &Self::Move) {
                 match (self, mv) {
                     $((GameWrapper::$variant(g), MoveWrapper::$variant(m)) => g.make_move(m),)*
@@ -249,6 +265,18 @@ macro_rules! impl_game_dispatch {
                 }
             }
 
+            fn is_drawn_position(&self) -> bool {
+                match self {
+                    $(GameWrapper::$variant(g) => g.is_drawn_position(),)*
+                }
+            }
+
+            fn get_winners(&self) -> Vec<i32> {
+                match self {
+                    $(GameWrapper::$variant(g) => g.get_winners(),)*
+                }
+            }
+
             fn get_board(&self) -> &Vec<Vec<i32>> {
                 match self {
                     $(GameWrapper::$variant(g) => g.get_board(),)*
@@ -260,6 +288,117 @@ macro_rules! impl_game_dispatch {
                     $(GameWrapper::$variant(g) => g.get_gpu_simulation_data(),)*
                 }
             }
+
+            fn get_gpu_legal_mask(&self) -> Option<Vec<u32>> {
+                match self {
+                    $(GameWrapper::$variant(g) => g.get_gpu_legal_mask(),)*
+                }
+            }
+
+            fn format_move(&self, mv: &Self::Move) -> String {
+                match (self, mv) {
+                    $((GameWrapper::$variant(g), MoveWrapper::$variant(m)) => g.format_move(m),)*
+                    _ => format!("{:?}", mv),
+                }
+            }
+
+            fn supports_gpu(&self) -> bool {
+                match self {
+                    $(GameWrapper::$variant(g) => g.supports_gpu(),)*
+                }
+            }
+
+            fn input_kind(&self) -> InputKind {
+                match self {
+                    $(GameWrapper::$variant(g) => g.input_kind(),)*
+                }
+            }
+
+            fn state_fingerprint(&self) -> u64 {
+                match self {
+                    $(GameWrapper::$variant(g) => g.state_fingerprint(),)*
+                }
+            }
+
+            fn current_hash(&self) -> u64 {
+                match self {
+                    $(GameWrapper::$variant(g) => g.current_hash(),)*
+                }
+            }
+
+            fn feature_planes(&self) -> Option<Vec<Vec<Vec<f32>>>> {
+                match self {
+                    $(GameWrapper::$variant(g) => g.feature_planes(),)*
+                }
+            }
+
+            fn last_move_effects(&self) -> Option<MoveEffects> {
+                match self {
+                    $(GameWrapper::$variant(g) => g.last_move_effects(),)*
+                }
+            }
+
+            fn material_balance(&self) -> Option<f64> {
+                match self {
+                    $(GameWrapper::$variant(g) => g.material_balance(),)*
+                }
+            }
+
+            fn heuristic_value(&self) -> Option<f64> {
+                match self {
+                    $(GameWrapper::$variant(g) => g.heuristic_value(),)*
+                }
+            }
+
+            fn player_index(&self, player: i32) -> Option<usize> {
+                match self {
+                    $(GameWrapper::$variant(g) => g.player_index(player),)*
+                }
+            }
+
+            fn game_phase(&self) -> GamePhase {
+                match self {
+                    $(GameWrapper::$variant(g) => g.game_phase(),)*
+                }
+            }
+
+            fn get_board_oriented(&self, rotation: u8) -> Vec<Vec<i32>> {
+                match self {
+                    $(GameWrapper::$variant(g) => g.get_board_oriented(rotation),)*
+                }
+            }
+
+            fn board_diff(&self, prev: &Self) -> Vec<(usize, usize, i32)> {
+                match (self, prev) {
+                    $((GameWrapper::$variant(g), GameWrapper::$variant(p)) => g.board_diff(p),)*
+                    // A different game replaced the board, so every cell changed
+                    _ => self
+                        .get_board()
+                        .iter()
+                        .enumerate()
+                        .flat_map(|(r, row)| row.iter().enumerate().map(move |(c, &cell)| (r, c, cell)))
+                        .collect(),
+                }
+            }
+
+            fn terminal_rewards(&self) -> Option<Vec<f64>> {
+                match self {
+                    $(GameWrapper::$variant(g) => g.terminal_rewards(),)*
+                }
+            }
+
+            fn is_single_player(&self) -> bool {
+                match self {
+                    $(GameWrapper::$variant(g) => g.is_single_player(),)*
+                }
+            }
+
+            fn tiebreak_value(&self, mv: &MoveWrapper) -> f64 {
+                match (self, mv) {
+                    $((GameWrapper::$variant(g), MoveWrapper::$variant(m)) => g.tiebreak_value(m),)*
+                    _ => 0.0,
+                }
+            }
         }
 
         #[allow(dead_code)]
@@ -301,6 +440,67 @@ mod tests {
     use super::*;
     use crate::games::gomoku::{GomokuMove, GomokuState};
 
+    #[test]
+    fn test_input_kind() {
+        let expected = [
+            (GameWrapper::Gomoku(GomokuState::new(15, 5)), InputKind::CellClick),
+            (GameWrapper::Othello(OthelloState::new(8)), InputKind::CellClick),
+            (GameWrapper::Connect4(Connect4State::new(7, 6, 4)), InputKind::ColumnClick),
+            (GameWrapper::Blokus(BlokusState::new()), InputKind::PieceAndPlacement),
+            (GameWrapper::Hive(HiveState::new()), InputKind::DragMove),
+        ];
+        for (game, kind) in expected {
+            assert_eq!(game.input_kind(), kind);
+        }
+    }
+
+    #[test]
+    fn test_game_phase_is_forwarded() {
+        let game = GameWrapper::Othello(OthelloState::new(8));
+        assert_eq!(game.game_phase(), GamePhase::Opening);
+    }
+
+    #[test]
+    fn test_board_oriented_is_forwarded() {
+        let mut inner = Connect4State::new(7, 6, 4);
+        inner.make_move(&Connect4Move(0));
+        let game = GameWrapper::Connect4(inner.clone());
+        assert_eq!(game.get_board_oriented(1), inner.get_board_oriented(1));
+    }
+
+    #[test]
+    fn test_board_diff_is_forwarded() {
+        let prev = OthelloState::new(8);
+        let mut next = prev.clone();
+        let mv = next.get_possible_moves()[0].clone();
+        next.make_move(&mv);
+        let diff = GameWrapper::Othello(next.clone()).board_diff(&GameWrapper::Othello(prev.clone()));
+        assert_eq!(diff, next.board_diff(&prev));
+        assert_eq!(diff.len(), 2, "the placed disc and one flip");
+
+        // Switching games reports the whole new board
+        let other = GameWrapper::Connect4(Connect4State::new(7, 6, 4));
+        assert_eq!(other.board_diff(&GameWrapper::Othello(prev)).len(), 42);
+    }
+
+    #[test]
+    fn test_terminal_rewards_are_forwarded() {
+        let mut inner = Connect4State::new(7, 6, 4);
+        for col in [0, 1, 0, 1, 0, 1, 0] {
+            inner.make_move(&Connect4Move(col));
+        }
+        let game = GameWrapper::Connect4(inner.clone());
+        assert!(game.is_terminal());
+        assert_eq!(game.terminal_rewards(), inner.terminal_rewards());
+    }
+
+    #[test]
+    fn test_is_single_player_is_forwarded() {
+        let game = GameWrapper::Hive(HiveState::new());
+        assert_eq!(game.is_single_player(), HiveState::new().is_single_player());
+        assert!(!game.is_single_player());
+    }
+
     #[test]
     fn test_display() {
         let move_wrapper = MoveWrapper::Gomoku(GomokuMove(1, 2));
@@ -27,7 +27,8 @@
 //! - All piece transformations (rotations + reflections) pre-computed for efficiency
 //! - Move validation includes adjacency rules and corner-touching requirements
 
-use crate::GameState;
+use crate::{GameState, InputKind};
+use crate::games::{DecodeError, WireMove, fixed_bytes};
 use std::collections::HashSet;
 use std::fmt;
 use std::str::FromStr;
@@ -282,6 +283,10 @@ impl GameState for BlokusState {
         &self.board
     }
 
+    fn supports_gpu(&self) -> bool {
+        true
+    }
+
     fn get_gpu_simulation_data(&self) -> Option<(Vec<i32>, usize, usize, i32)> {
         let mut data = Vec::with_capacity(20 * 21);
         
@@ -330,9 +335,12 @@ impl GameState for BlokusState {
         
         // Consecutive passes
         data.push(self.consecutive_passes as i32);
+
+        // Player to move, so boards with different players can share a batch
+        data.push(self.current_player);
         
         // Fill rest of the row with 0s
-        for _ in 6..20 {
+        for _ in 7..20 {
             data.push(0);
         }
         
@@ -341,6 +349,8 @@ impl GameState for BlokusState {
         Some((data, 20, 21, encoded_params))
     }
 
+    /// Pieces in hand order (ascending id), then transformation, then anchor cell
+    /// in row-major order
     fn get_possible_moves(&self) -> Vec<Self::Move> {
         let player_idx = (self.current_player - 1) as usize;
         let available_pieces = &self.player_pieces[player_idx];
@@ -444,8 +454,16 @@ impl GameState for BlokusState {
     }
 
     fn get_winner(&self) -> Option<i32> {
+        match self.get_winners().as_slice() {
+            &[winner] => Some(winner),
+            // In case of a tie, return None (Draw)
+            _ => None,
+        }
+    }
+
+    fn get_winners(&self) -> Vec<i32> {
         if !self.is_terminal() {
-            return None;
+            return Vec::new();
         }
 
         // Calculate scores: count remaining squares for each player (lower is better)
@@ -458,18 +476,12 @@ impl GameState for BlokusState {
         }
 
         let min_score = *scores.iter().min().unwrap();
-        let winners: Vec<_> = scores
+        scores
             .iter()
             .enumerate()
             .filter(|&(_, &s)| s == min_score)
-            .collect();
-
-        if winners.len() == 1 {
-            Some((winners[0].0 + 1) as i32)
-        } else {
-            // In case of a tie, return None (Draw)
-            None
-        }
+            .map(|(i, _)| (i + 1) as i32)
+            .collect()
     }
 
     fn get_last_move(&self) -> Option<Vec<(usize, usize)>> {
@@ -479,6 +491,27 @@ impl GameState for BlokusState {
     fn get_current_player(&self) -> i32 {
         self.current_player
     }
+
+    fn input_kind(&self) -> InputKind {
+        InputKind::PieceAndPlacement
+    }
+
+    fn material_balance(&self) -> Option<f64> {
+        // Squares covered by the player to move minus those of the leading opponent
+        let mut covered = [0i32; 4];
+        for &cell in self.board.iter().flatten() {
+            if (1..=4).contains(&cell) {
+                covered[(cell - 1) as usize] += 1;
+            }
+        }
+        let own = covered[(self.current_player - 1) as usize];
+        let best_opponent = (1..=4)
+            .filter(|&p| p != self.current_player)
+            .map(|p| covered[(p - 1) as usize])
+            .max()
+            .unwrap_or(0);
+        Some((own - best_opponent) as f64)
+    }
 }
 
 impl BlokusState {
@@ -766,10 +799,81 @@ impl FromStr for BlokusMove {
     }
 }
 
+/// Byte marking the pass move in the wire encoding
+const WIRE_PASS: u8 = u8::MAX;
+
+impl WireMove for BlokusMove {
+    /// Four bytes: piece, transformation, row and column, or `WIRE_PASS` then zeros for a pass
+    fn encode(&self) -> Vec<u8> {
+        if *self == PASS_MOVE {
+            return vec![WIRE_PASS, 0, 0, 0];
+        }
+        assert!(
+            self.0 < 21 && self.1 < 8 && self.2 < 20 && self.3 < 20,
+            "Blokus move {:?} is out of range",
+            self
+        );
+        vec![self.0 as u8, self.1 as u8, self.2 as u8, self.3 as u8]
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let [piece, transformation, row, col] = fixed_bytes(bytes)?;
+        if piece == WIRE_PASS {
+            return Ok(PASS_MOVE);
+        }
+        // 21 pieces with up to 8 transformations on a 20x20 board
+        for (byte, limit) in [(piece, 21), (transformation, 8), (row, 20), (col, 20)] {
+            if byte >= limit {
+                return Err(DecodeError::InvalidByte(byte));
+            }
+        }
+        Ok(BlokusMove(piece as usize, transformation as usize, row as usize, col as usize))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[cfg(feature = "gpu")]
+    #[test]
+    fn test_gpu_scores_from_player_to_move() {
+        use crate::MCTS;
+
+        // Player 3 owns the only stones and nobody has pieces left, so every
+        // rollout ends at once with player 3 ahead
+        let mut state = BlokusState::new();
+        for c in 0..5 {
+            state.board[10][c] = 3;
+        }
+        state.player_pieces.iter_mut().for_each(|pieces| pieces.clear());
+        state.is_first_move = [false; 4];
+
+        let (mcts, _) = MCTS::<BlokusState>::with_gpu(1.4, 1, 1000);
+        if !mcts.is_gpu_enabled() {
+            return;
+        }
+        let mut player3 = state.clone();
+        player3.current_player = 3;
+        let mut player1 = state;
+        player1.current_player = 1;
+        assert_eq!(mcts.gpu_evaluate_batch(&[player3, player1]), Some(vec![1.0, 0.0]));
+    }
+
+    #[test]
+    fn test_wire_move_round_trip() {
+        for mv in [BlokusMove(0, 0, 0, 0), BlokusMove(20, 7, 19, 12), PASS_MOVE] {
+            assert_eq!(mv.encode().len(), 4);
+            assert_eq!(BlokusMove::decode(&mv.encode()), Ok(mv));
+        }
+        assert_eq!(
+            BlokusMove::decode(&[1, 2, 3]),
+            Err(DecodeError::Length { expected: 4, found: 3 })
+        );
+        assert_eq!(BlokusMove::decode(&[21, 0, 0, 0]), Err(DecodeError::InvalidByte(21)));
+        assert_eq!(BlokusMove::decode(&[0, 0, 0, 20]), Err(DecodeError::InvalidByte(20)));
+    }
+
     #[test]
     fn test_new_game() {
         let game = BlokusState::new();
@@ -783,6 +887,15 @@ mod tests {
         assert_eq!(pieces.len(), 21);
     }
 
+    #[test]
+    fn test_move_order() {
+        let moves = BlokusState::new().get_possible_moves();
+        assert_eq!(moves[0], BlokusMove(0, 0, 0, 0));
+        // Piece id, then transformation, then anchor cell in row-major order
+        let key = |mv: &BlokusMove| (mv.0, mv.1, mv.2, mv.3);
+        assert!(moves.windows(2).all(|pair| key(&pair[0]) < key(&pair[1])));
+    }
+
     #[test]
     fn test_piece_generation() {
         let pieces = get_blokus_pieces();
@@ -958,6 +1071,22 @@ mod tests {
         
         assert!(game.is_terminal());
         assert_eq!(game.get_winner(), None);
+        assert_eq!(game.get_winners(), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_two_way_tie_winners() {
+        let mut game = BlokusState::new();
+        // P1 and P2 each place a monomino, then everyone passes
+        game.make_move(&BlokusMove(0, 0, 0, 0));
+        game.make_move(&BlokusMove(0, 0, 0, 19));
+        for _ in 0..4 {
+            game.make_move(&PASS_MOVE);
+        }
+
+        assert!(game.is_terminal());
+        assert_eq!(game.get_winner(), None);
+        assert_eq!(game.get_winners(), vec![1, 2]);
     }
 
     #[test]
@@ -989,6 +1118,10 @@ mod tests {
         assert_eq!(width, 20);
         assert_eq!(height, 21);
         assert_eq!(vec.len(), 20 * 21);
+        // State row: all 21 pieces available, every player on their first move, player 1 to move
+        assert_eq!(vec[400], (1 << 21) - 1);
+        assert_eq!(vec[404], 0b1111);
+        assert_eq!(vec[406], 1);
     }
     
     #[test]
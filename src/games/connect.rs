@@ -0,0 +1,487 @@
+//! # N-in-a-Row Connection Games
+//!
+//! This module implements a generic "connect N on a grid" game. With gravity,
+//! pieces fall to the lowest empty cell of a column (Connect 4); without it,
+//! pieces go on any empty cell (Gomoku).
+//!
+//! The rules shared by all connection games live here: the line check through
+//! the last move, the gravity drop and the GPU board encoding. `Connect4State`
+//! and `GomokuState` wrap a `ConnectState` for their board, moves and winner,
+//! and add their game-specific extras.
+//!
+//! ## Rules
+//! - Players alternate placing pieces, dropping them into a column with gravity
+//! - First player to get N pieces in a row wins
+//! - The line can be horizontal, vertical, or diagonal
+//! - Game is a draw if the board fills up with no winner
+
+use crate::{GamePhase, GameState, InputKind};
+use std::fmt;
+use std::str::FromStr;
+
+/// The four line directions as (row, column) steps
+const DIRECTIONS: [(isize, isize); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
+
+/// Returns the owner of a line of at least `line_size` pieces through `(r, c)`
+pub(crate) fn line_winner(board: &[Vec<i32>], (r, c): (usize, usize), line_size: usize) -> Option<i32> {
+    let player = board[r][c];
+    if player == 0 {
+        return None;
+    }
+    let owned = |row: isize, col: isize| {
+        usize::try_from(row).ok().zip(usize::try_from(col).ok()).is_some_and(|(row, col)| {
+            board.get(row).and_then(|cells| cells.get(col)) == Some(&player)
+        })
+    };
+    let run = |dr: isize, dc: isize| {
+        (1..line_size as isize)
+            .take_while(|&i| owned(r as isize + dr * i, c as isize + dc * i))
+            .count()
+    };
+    DIRECTIONS
+        .iter()
+        .any(|&(dr, dc)| 1 + run(dr, dc) + run(-dr, -dc) >= line_size)
+        .then_some(player)
+}
+
+/// Returns the row a piece dropped into `col` lands on, or None if the column is full
+///
+/// Row 0 is the top of the board.
+pub(crate) fn drop_row(board: &[Vec<i32>], col: usize) -> Option<usize> {
+    (0..board.len()).rev().find(|&r| board[r][col] == 0)
+}
+
+/// Static evaluation of a connection game for `player`, in [-1, 1]
+///
+/// Every window of `line_size` cells that only one player occupies counts the
+/// square of its pieces for that player. Being able to complete a line with the
+/// next move is a near-certain win, and facing two such threats a near-certain loss.
+pub(crate) fn line_heuristic(board: &[Vec<i32>], player: i32, line_size: usize, gravity: bool) -> f64 {
+    let height = board.len() as isize;
+    let width = board.first().map_or(0, Vec::len) as isize;
+    let playable = |r: usize, c: usize| {
+        if gravity { drop_row(board, c) == Some(r) } else { board[r][c] == 0 }
+    };
+    let span = line_size as isize - 1;
+    let mut score = 0.0;
+    let mut threats = Vec::new();
+    for r in 0..height {
+        for c in 0..width {
+            for &(dr, dc) in &DIRECTIONS {
+                let (end_r, end_c) = (r + dr * span, c + dc * span);
+                if !(0..height).contains(&end_r) || !(0..width).contains(&end_c) {
+                    continue;
+                }
+                let cells = (0..line_size as isize).map(|i| ((r + dr * i) as usize, (c + dc * i) as usize));
+                let (mut mine, mut theirs, mut empty) = (0, 0, None);
+                for (row, col) in cells {
+                    match board[row][col] {
+                        0 => empty = Some((row, col)),
+                        cell if cell == player => mine += 1,
+                        _ => theirs += 1,
+                    }
+                }
+                let threat = empty.filter(|&(row, col)| playable(row, col));
+                if theirs == 0 {
+                    if mine == line_size - 1 && threat.is_some() {
+                        return 0.95;
+                    }
+                    score += (mine * mine) as f64;
+                } else if mine == 0 {
+                    if let Some(cell) = threat.filter(|_| theirs == line_size - 1)
+                        && !threats.contains(&cell)
+                    {
+                        threats.push(cell);
+                    }
+                    score -= (theirs * theirs) as f64;
+                }
+            }
+        }
+    }
+    if threats.len() >= 2 { -0.95 } else { (score / 16.0).tanh() }
+}
+
+/// Returns true if the GPU shaders can play out a connection game with these rules
+///
+/// Gravity games pick the gravity shader through their encoded line size, which
+/// must stay below 10. The free-placement shader always plays five in a row.
+pub(crate) fn gpu_supports(gravity: bool, line_size: usize) -> bool {
+    if gravity { (1..10).contains(&line_size) } else { line_size == 5 }
+}
+
+/// Encodes a board for GPU rollouts with the player to move normalized to 1
+///
+/// Gravity games pass their line size to pick the gravity shader; free-placement
+/// games use the Gomoku shader. Only valid when `gpu_supports` holds.
+pub(crate) fn gpu_simulation_data(
+    board: &[Vec<i32>],
+    current_player: i32,
+    gravity: bool,
+    line_size: usize,
+) -> (Vec<i32>, usize, usize, i32) {
+    let height = board.len();
+    let width = board.first().map_or(0, Vec::len);
+    // Normalize board so current player is always 1
+    // This allows batching states with different current players
+    let multiplier = if current_player == 1 { 1 } else { -1 };
+    let data = board.iter().flatten().map(|&cell| cell * multiplier).collect();
+    // Format: player in bits 0-7, line_size in bits 8-15 for gravity games
+    let encoded_params = if gravity { 1 | ((line_size as i32) << 8) } else { 1 };
+    (data, width, height, encoded_params)
+}
+
+/// Represents a move in a connection game
+///
+/// Contains the (row, column) of the placed piece, 0-based. With gravity the
+/// piece always lands on the lowest empty cell of the column.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ConnectMove(pub usize, pub usize);
+
+/// Represents the complete state of a connection game
+///
+/// The board uses 1 for player 1 pieces, -1 for player 2 pieces, and 0 for empty spaces.
+#[derive(Debug, Clone)]
+pub struct ConnectState {
+    /// The game board as a 2D vector (rows x columns)
+    board: Vec<Vec<i32>>,
+    /// Current player (1 or -1)
+    current_player: i32,
+    /// Number of pieces needed in a row to win
+    line_size: usize,
+    /// Whether pieces fall to the bottom of their column
+    gravity: bool,
+    /// Last move made, if any (row, column)
+    last_move: Option<(usize, usize)>,
+}
+
+impl ConnectState {
+    /// Creates a new connection game
+    ///
+    /// `ConnectState::new(7, 6, 4, true)` is Connect 4 and
+    /// `ConnectState::new(15, 15, 5, false)` is Gomoku.
+    ///
+    /// # Arguments
+    /// * `width` - Number of columns
+    /// * `height` - Number of rows
+    /// * `line_size` - Number of pieces needed in a row to win
+    /// * `gravity` - Whether pieces fall to the bottom of their column
+    pub fn new(width: usize, height: usize, line_size: usize, gravity: bool) -> Self {
+        Self {
+            board: vec![vec![0; width]; height],
+            current_player: 1,
+            line_size,
+            gravity,
+            last_move: None,
+        }
+    }
+
+    /// Creates a game from an arbitrary legal board
+    ///
+    /// Row 0 is the top of the board. The board is rejected if the piece counts
+    /// don't match `current_player`, the player to move has a line, or (with
+    /// gravity) a piece is floating. If the previous player has a line, the game
+    /// is over.
+    ///
+    /// # Arguments
+    /// * `board` - Rows of cells (1, -1 or 0)
+    /// * `current_player` - The player to move (1 or -1)
+    /// * `line_size` - Number of pieces needed in a row to win
+    /// * `gravity` - Whether pieces fall to the bottom of their column
+    pub fn from_board(board: Vec<Vec<i32>>, current_player: i32, line_size: usize, gravity: bool) -> Result<Self, String> {
+        super::validate_two_player_board(&board, current_player)?;
+        if gravity {
+            let width = board[0].len();
+            for (r, rows) in board.windows(2).enumerate() {
+                if let Some(c) = (0..width).find(|&c| rows[0][c] != 0 && rows[1][c] == 0) {
+                    return Err(format!("Floating piece at row {}, column {}", r, c));
+                }
+            }
+        }
+
+        let last_move = super::derive_last_move(&board, current_player, |pos| line_winner(&board, pos, line_size))?;
+        Ok(Self::from_position(board, current_player, line_size, gravity, last_move))
+    }
+
+    /// Creates a game from a board without checking it
+    pub(crate) fn from_position(
+        board: Vec<Vec<i32>>,
+        current_player: i32,
+        line_size: usize,
+        gravity: bool,
+        last_move: Option<(usize, usize)>,
+    ) -> Self {
+        Self { board, current_player, line_size, gravity, last_move }
+    }
+
+    /// Returns the number of columns
+    pub fn get_width(&self) -> usize {
+        self.board.first().map_or(0, Vec::len)
+    }
+
+    /// Returns the number of rows
+    pub fn get_height(&self) -> usize {
+        self.board.len()
+    }
+
+    /// Returns the number of pieces needed in a row to win
+    pub fn get_line_size(&self) -> usize {
+        self.line_size
+    }
+
+    /// Returns the (row, column) of the last move, if any
+    pub(crate) fn last_move(&self) -> Option<(usize, usize)> {
+        self.last_move
+    }
+
+    /// Columns with room for another piece, left to right
+    pub(crate) fn open_columns(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.get_width()).filter(|&c| self.board[0][c] == 0)
+    }
+
+    /// Empty cells in row-major order
+    pub(crate) fn empty_cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let width = self.get_width();
+        (0..self.board.len())
+            .flat_map(move |r| (0..width).map(move |c| (r, c)))
+            .filter(|&(r, c)| self.board[r][c] == 0)
+    }
+
+    /// Puts a piece of the player to move on `(row, col)` and passes the turn
+    ///
+    /// The caller has already applied gravity.
+    pub(crate) fn place(&mut self, row: usize, col: usize) {
+        self.board[row][col] = self.current_player;
+        self.last_move = Some((row, col));
+        self.current_player = -self.current_player;
+    }
+
+    /// Returns whether pieces fall to the bottom of their column
+    pub fn has_gravity(&self) -> bool {
+        self.gravity
+    }
+
+    /// Checks if a move is legal in the current game state
+    ///
+    /// The cell must be on the board and empty, and with gravity it must be
+    /// the lowest empty cell of its column.
+    ///
+    /// # Arguments
+    /// * `mv` - The move to check
+    ///
+    /// # Returns
+    /// True if the move is legal, false otherwise
+    pub fn is_legal(&self, mv: &ConnectMove) -> bool {
+        let on_board = mv.0 < self.board.len() && mv.1 < self.board[0].len();
+        on_board
+            && if self.gravity {
+                drop_row(&self.board, mv.1) == Some(mv.0)
+            } else {
+                self.board[mv.0][mv.1] == 0
+            }
+    }
+}
+
+impl fmt::Display for ConnectState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in &self.board {
+            for &cell in row {
+                let symbol = match cell {
+                    1 => "X",
+                    -1 => "O",
+                    _ => ".",
+                };
+                write!(f, "{} ", symbol)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl GameState for ConnectState {
+    type Move = ConnectMove;
+
+    fn get_num_players(&self) -> i32 {
+        2
+    }
+
+    fn get_board(&self) -> &Vec<Vec<i32>> {
+        &self.board
+    }
+
+    fn get_last_move(&self) -> Option<Vec<(usize, usize)>> {
+        self.last_move.map(|(r, c)| vec![(r, c)])
+    }
+
+    fn format_move(&self, mv: &Self::Move) -> String {
+        if self.gravity {
+            format!("col {}", mv.1 + 1)
+        } else {
+            format!("{},{}", mv.0, mv.1)
+        }
+    }
+
+    fn supports_gpu(&self) -> bool {
+        gpu_supports(self.gravity, self.line_size)
+    }
+
+    fn input_kind(&self) -> InputKind {
+        if self.gravity { InputKind::ColumnClick } else { InputKind::CellClick }
+    }
+
+    fn get_gpu_simulation_data(&self) -> Option<(Vec<i32>, usize, usize, i32)> {
+        gpu_supports(self.gravity, self.line_size)
+            .then(|| gpu_simulation_data(&self.board, self.current_player, self.gravity, self.line_size))
+    }
+
+    fn heuristic_value(&self) -> Option<f64> {
+        Some(line_heuristic(&self.board, self.current_player, self.line_size, self.gravity))
+    }
+
+    /// Open columns from left to right with gravity, empty cells in row-major order without
+    fn get_possible_moves(&self) -> Vec<Self::Move> {
+        if self.gravity {
+            self.open_columns()
+                .filter_map(|c| drop_row(&self.board, c).map(|r| ConnectMove(r, c)))
+                .collect()
+        } else {
+            self.empty_cells().map(|(r, c)| ConnectMove(r, c)).collect()
+        }
+    }
+
+    fn make_move(&mut self, mv: &Self::Move) {
+        let row = if self.gravity {
+            match drop_row(&self.board, mv.1) {
+                Some(row) => row,
+                None => return,
+            }
+        } else {
+            mv.0
+        };
+        self.place(row, mv.1);
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.get_winner().is_some() || self.get_possible_moves().is_empty()
+    }
+
+    fn get_winner(&self) -> Option<i32> {
+        line_winner(&self.board, self.last_move?, self.line_size)
+    }
+
+    fn get_current_player(&self) -> i32 {
+        self.current_player
+    }
+
+    fn game_phase(&self) -> GamePhase {
+        let filled = self.board.iter().flatten().filter(|&&cell| cell != 0).count();
+        GamePhase::from_fill_ratio(filled, self.get_height() * self.get_width())
+    }
+}
+
+impl FromStr for ConnectMove {
+    type Err = String;
+
+    /// Creates a ConnectMove from a string representation
+    ///
+    /// Expected format is "row,col" where both are 0-based indices.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split(',').map(|s| s.trim()).collect();
+        if parts.len() != 2 {
+            return Err("Expected format: r,c".to_string());
+        }
+        let r = parts[0].parse::<usize>().map_err(|e| e.to_string())?;
+        let c = parts[1].parse::<usize>().map_err(|e| e.to_string())?;
+        Ok(ConnectMove(r, c))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::connect4::{Connect4Move, Connect4State};
+    use crate::games::gomoku::{GomokuMove, GomokuState};
+
+    #[test]
+    fn test_gravity_matches_connect4() {
+        let mut game = ConnectState::new(7, 6, 4, true);
+        let mut reference = Connect4State::new(7, 6, 4);
+        assert_eq!(game.get_possible_moves().len(), 7);
+        assert!(game.get_possible_moves().iter().all(|mv| mv.0 == 5));
+
+        // A piece dropped onto another stacks on top, whatever row the move names
+        for col in [0, 0, 1, 1, 2, 2, 3] {
+            game.make_move(&ConnectMove(0, col));
+            reference.make_move(&Connect4Move(col));
+            assert_eq!(game.get_board(), reference.get_board());
+            assert_eq!(game.get_winner(), reference.get_winner());
+        }
+        assert_eq!(game.get_board()[4][0], -1);
+        assert!(!game.is_legal(&ConnectMove(5, 0)));
+        assert!(game.is_legal(&ConnectMove(3, 0)));
+        assert_eq!(game.get_winner(), Some(1));
+        assert_eq!(game.get_gpu_simulation_data(), reference.get_gpu_simulation_data());
+    }
+
+    #[test]
+    fn test_move_order() {
+        // With gravity: one landing cell per open column, left to right
+        let mut game = ConnectState::new(3, 2, 3, true);
+        game.make_move(&ConnectMove(1, 1));
+        assert_eq!(game.get_possible_moves(), vec![ConnectMove(1, 0), ConnectMove(0, 1), ConnectMove(1, 2)]);
+
+        // Without: empty cells in row-major order
+        let mut game = ConnectState::new(3, 2, 3, false);
+        game.make_move(&ConnectMove(0, 1));
+        let expected: Vec<_> = [(0, 0), (0, 2), (1, 0), (1, 1), (1, 2)]
+            .into_iter()
+            .map(|(r, c)| ConnectMove(r, c))
+            .collect();
+        assert_eq!(game.get_possible_moves(), expected);
+    }
+
+    #[test]
+    fn test_free_placement_matches_gomoku() {
+        let mut game = ConnectState::new(15, 15, 5, false);
+        let mut reference = GomokuState::new(15, 5);
+        assert_eq!(game.get_possible_moves().len(), 15 * 15);
+
+        // Pieces stay where they are placed; P1 completes a diagonal
+        for (r, c) in [(0, 0), (14, 0), (1, 1), (14, 1), (2, 2), (14, 2), (3, 3), (14, 3), (4, 4)] {
+            assert!(game.is_legal(&ConnectMove(r, c)));
+            game.make_move(&ConnectMove(r, c));
+            reference.make_move(&GomokuMove(r, c));
+            assert_eq!(game.get_board(), reference.get_board());
+            assert_eq!(game.get_winner(), reference.get_winner());
+        }
+        assert_eq!(game.get_board()[0][0], 1);
+        assert!(!game.is_legal(&ConnectMove(0, 0)));
+        assert_eq!(game.get_winner(), Some(1));
+        assert!(game.is_terminal());
+        assert_eq!(game.get_gpu_simulation_data(), reference.get_gpu_simulation_data());
+    }
+
+    #[test]
+    fn test_gpu_support_requires_encodable_line_size() {
+        // The free-placement shader only plays five in a row
+        let game = ConnectState::new(3, 3, 3, false);
+        assert!(!game.supports_gpu());
+        assert!(game.get_gpu_simulation_data().is_none());
+        assert!(ConnectState::new(15, 15, 5, false).supports_gpu());
+        assert!(ConnectState::new(3, 3, 3, true).supports_gpu());
+        assert!(!ConnectState::new(12, 12, 10, true).supports_gpu());
+    }
+
+    #[test]
+    fn test_from_board_matches_connect4() {
+        let board = vec![vec![0, 0, 0], vec![0, 0, 0], vec![1, -1, 0]];
+        let game = ConnectState::from_board(board.clone(), 1, 3, true).unwrap();
+        let reference = Connect4State::from_board(board, 1, 3).unwrap();
+        assert_eq!(game.get_board(), reference.get_board());
+        assert_eq!(game.get_possible_moves().len(), 3);
+
+        let floating = vec![vec![0, 1, 0], vec![0, 0, 0], vec![0, -1, 0]];
+        assert!(ConnectState::from_board(floating.clone(), 1, 3, true).is_err());
+        assert!(ConnectState::from_board(floating, 1, 3, false).is_ok());
+    }
+}
@@ -10,9 +10,15 @@
 //! - First player to get 4 pieces in a row wins
 //! - Game is a draw if the board fills up with no winner
 
-use crate::GameState;
+use crate::games::connect::{ConnectState, drop_row, line_winner};
+use crate::games::{DecodeError, WireMove, ZobristTable, fixed_bytes, two_player_planes};
+use crate::{GamePhase, GameState, InputKind};
 use std::fmt;
 use std::str::FromStr;
+use std::sync::Arc;
+
+/// Seed for the Zobrist keys, fixed so equal positions hash alike across games
+const ZOBRIST_SEED: u64 = 0x000C_0EEC_7404;
 
 /// Represents a move in Connect 4
 ///
@@ -21,40 +27,36 @@ use std::str::FromStr;
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct Connect4Move(pub usize);
 
+/// Rule variant deciding the outcome when the board fills up without a line
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Connect4Variant {
+    /// A full board is a draw (standard rules)
+    #[default]
+    Draw,
+    /// The player who filled the last cell wins
+    LastMoverWins,
+}
+
 /// Represents the complete state of a Connect 4 game
 ///
-/// Contains the board state, current player, dimensions, and move history.
+/// A gravity `ConnectState` holds the board, current player and last move; this
+/// type adds the full-board variant and incremental Zobrist hashing.
 /// The board uses 1 for player 1 pieces, -1 for player 2 pieces, and 0 for empty spaces.
 #[derive(Debug, Clone)]
 pub struct Connect4State {
-    /// The game board as a 2D vector (rows x columns)
-    board: Vec<Vec<i32>>,
-    /// Current player (1 or -1)
-    current_player: i32,
-    /// Board width (number of columns)
-    width: usize,
-    /// Board height (number of rows)
-    height: usize,
-    /// Number of pieces needed in a row to win
-    line_size: usize,
-    /// Last move made, if any (row, column)
-    last_move: Option<(usize, usize)>,
+    /// The underlying connection game, always with gravity
+    game: ConnectState,
+    /// Outcome rule for a full board without a line
+    variant: Connect4Variant,
+    /// Zobrist keys shared by every clone of the game
+    zobrist: Arc<ZobristTable>,
+    /// Zobrist hash of the position, updated by `make_move`
+    hash: u64,
 }
 
 impl fmt::Display for Connect4State {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for row in &self.board {
-            for &cell in row {
-                let symbol = match cell {
-                    1 => "X",
-                    -1 => "O",
-                    _ => ".",
-                };
-                write!(f, "{} ", symbol)?;
-            }
-            writeln!(f)?;
-        }
-        Ok(())
+        fmt::Display::fmt(&self.game, f)
     }
 }
 
@@ -66,44 +68,99 @@ impl GameState for Connect4State {
     }
 
     fn get_board(&self) -> &Vec<Vec<i32>> {
-        &self.board
+        self.game.get_board()
     }
 
     fn get_last_move(&self) -> Option<Vec<(usize, usize)>> {
-        self.last_move.map(|(r, c)| vec![(r, c)])
+        self.game.get_last_move()
     }
 
-    fn get_gpu_simulation_data(&self) -> Option<(Vec<i32>, usize, usize, i32)> {
-        let mut data = Vec::with_capacity(self.height * self.width);
-        // Normalize board so current player is always 1
-        // This allows batching states with different current players
-        let multiplier = if self.current_player == 1 { 1 } else { -1 };
-        for row in &self.board {
-            for &cell in row {
-                data.push(cell * multiplier);
+    fn format_move(&self, mv: &Self::Move) -> String {
+        format!("col {}", mv.0 + 1)
+    }
+
+    fn supports_gpu(&self) -> bool {
+        self.game.supports_gpu()
+    }
+
+    fn input_kind(&self) -> InputKind {
+        InputKind::ColumnClick
+    }
+
+    fn state_fingerprint(&self) -> u64 {
+        // Each column packs its pieces bottom-up (1 for X) under a sentinel bit,
+        // which is exact as long as every column fits in the 64 bits
+        let (width, height) = (self.game.get_width(), self.game.get_height());
+        if width * (height + 1) > 64 {
+            return crate::debug::board_fingerprint(self.get_board(), self.get_current_player());
+        }
+        let mut key = 0u64;
+        for c in 0..width {
+            let mut column = 0u64;
+            let mut filled = 0;
+            for r in (0..height).rev() {
+                match self.get_board()[r][c] {
+                    0 => break,
+                    cell => column |= ((cell == 1) as u64) << filled,
+                }
+                filled += 1;
             }
+            column |= 1 << filled;
+            key |= column << (c * (height + 1));
         }
-        // Encode line_size in the player field (upper bits)
-        // Format: player in bits 0-7, line_size in bits 8-15
-        let encoded_params = 1 | ((self.line_size as i32) << 8);
-        Some((data, self.width, self.height, encoded_params))
+        key
+    }
+
+    fn current_hash(&self) -> u64 {
+        self.hash
     }
 
+    fn get_gpu_simulation_data(&self) -> Option<(Vec<i32>, usize, usize, i32)> {
+        self.game.get_gpu_simulation_data()
+    }
+
+    fn feature_planes(&self) -> Option<Vec<Vec<Vec<f32>>>> {
+        let last_move: Vec<_> = self.game.last_move().into_iter().collect();
+        Some(two_player_planes(self.get_board(), self.get_current_player(), &last_move))
+    }
+
+    fn heuristic_value(&self) -> Option<f64> {
+        self.game.heuristic_value()
+    }
+
+    /// Open columns from left to right
     fn get_possible_moves(&self) -> Vec<Self::Move> {
-        (0..self.width)
-            .filter(|&c| self.board[0][c] == 0)
-            .map(Connect4Move)
-            .collect()
+        self.game.open_columns().map(Connect4Move).collect()
+    }
+
+    /// Columns where either player's piece would complete a line one short of winning,
+    /// so wins, new threats and blocks of the opponent's threats, left to right
+    fn get_tactical_moves(&self) -> Option<Vec<Self::Move>> {
+        let threat_size = self.game.get_line_size().saturating_sub(1).max(1);
+        let current_player = self.get_current_player();
+        let mut board = self.get_board().clone();
+        let moves = (0..self.game.get_width())
+            .filter_map(|c| drop_row(self.get_board(), c).map(|r| (r, c)))
+            .filter(|&(r, c)| {
+                [current_player, -current_player].into_iter().any(|player| {
+                    board[r][c] = player;
+                    let tactical = line_winner(&board, (r, c), threat_size).is_some();
+                    board[r][c] = 0;
+                    tactical
+                })
+            })
+            .map(|(_, c)| Connect4Move(c))
+            .collect();
+        Some(moves)
     }
 
     fn make_move(&mut self, mv: &Self::Move) {
-        for r in (0..self.height).rev() {
-            if self.board[r][mv.0] == 0 {
-                self.board[r][mv.0] = self.current_player;
-                self.last_move = Some((r, mv.0));
-                self.current_player = -self.current_player;
-                return;
-            }
+        if let Some(r) = drop_row(self.get_board(), mv.0) {
+            let player = self.get_current_player();
+            self.hash ^= self.zobrist.piece_key(r * self.game.get_width() + mv.0, player)
+                ^ self.zobrist.player_key(player)
+                ^ self.zobrist.player_key(-player);
+            self.game.place(r, mv.0);
         }
     }
 
@@ -112,84 +169,17 @@ impl GameState for Connect4State {
     }
 
     fn get_winner(&self) -> Option<i32> {
-        let last_move = self.last_move?;
-        let (r, c) = last_move;
-        let player = self.board[r][c];
-
+        let (r, c) = self.game.last_move()?;
+        if let Some(winner) = self.game.get_winner() {
+            return Some(winner);
+        }
+        let player = self.get_board()[r][c];
         if player == 0 {
             return None;
         }
 
-        // Check horizontal
-        let mut count = 1;
-        for i in 1..self.line_size {
-            if c >= i && self.board[r][c - i] == player {
-                count += 1;
-            } else {
-                break;
-            }
-        }
-        for i in 1..self.line_size {
-            if c + i < self.width && self.board[r][c + i] == player {
-                count += 1;
-            } else {
-                break;
-            }
-        }
-        if count >= self.line_size {
-            return Some(player);
-        }
-
-        // Check vertical
-        let mut count = 1;
-        for i in 1..self.line_size {
-            if r + i < self.height && self.board[r + i][c] == player {
-                count += 1;
-            } else {
-                break;
-            }
-        }
-        if count >= self.line_size {
-            return Some(player);
-        }
-
-        // Check diagonal (top-left to bottom-right)
-        let mut count = 1;
-        for i in 1..self.line_size {
-            if r >= i && c >= i && self.board[r - i][c - i] == player {
-                count += 1;
-            } else {
-                break;
-            }
-        }
-        for i in 1..self.line_size {
-            if r + i < self.height && c + i < self.width && self.board[r + i][c + i] == player {
-                count += 1;
-            } else {
-                break;
-            }
-        }
-        if count >= self.line_size {
-            return Some(player);
-        }
-
-        // Check diagonal (top-right to bottom-left)
-        let mut count = 1;
-        for i in 1..self.line_size {
-            if r >= i && c + i < self.width && self.board[r - i][c + i] == player {
-                count += 1;
-            } else {
-                break;
-            }
-        }
-        for i in 1..self.line_size {
-            if r + i < self.height && c >= i && self.board[r + i][c - i] == player {
-                count += 1;
-            } else {
-                break;
-            }
-        }
-        if count >= self.line_size {
+        // No line: a full board is decided by the variant
+        if self.variant == Connect4Variant::LastMoverWins && self.get_possible_moves().is_empty() {
             return Some(player);
         }
 
@@ -197,29 +187,70 @@ impl GameState for Connect4State {
     }
 
     fn get_current_player(&self) -> i32 {
-        self.current_player
+        self.game.get_current_player()
+    }
+
+    fn game_phase(&self) -> GamePhase {
+        self.game.game_phase()
     }
 }
 
 impl Connect4State {
     /// Creates a new Connect 4 game with the specified configuration
     pub fn new(width: usize, height: usize, line_size: usize) -> Self {
+        Self::with_variant(width, height, line_size, Connect4Variant::default())
+    }
+
+    /// Creates a new Connect 4 game with a specific full-board rule
+    pub fn with_variant(width: usize, height: usize, line_size: usize, variant: Connect4Variant) -> Self {
+        let zobrist = Arc::new(ZobristTable::new(width * height, 2, ZOBRIST_SEED));
         Self {
-            board: vec![vec![0; width]; height],
-            current_player: 1,
-            width,
-            height,
-            line_size,
-            last_move: None,
+            game: ConnectState::new(width, height, line_size, true),
+            variant,
+            hash: zobrist.player_key(1),
+            zobrist,
         }
     }
 
+    /// Creates a game from an arbitrary legal board
+    ///
+    /// Row 0 is the top of the board. The board is rejected if it has floating
+    /// pieces, piece counts that don't match `current_player`, or a line for the
+    /// player to move. If the previous player has a line, the game is over.
+    ///
+    /// # Arguments
+    /// * `board` - Rows of cells (1, -1 or 0)
+    /// * `current_player` - The player to move (1 or -1)
+    /// * `line_size` - Number of pieces needed in a row to win
+    pub fn from_board(board: Vec<Vec<i32>>, current_player: i32, line_size: usize) -> Result<Self, String> {
+        let game = ConnectState::from_board(board, current_player, line_size, true)?;
+        let zobrist = Arc::new(ZobristTable::new(game.get_width() * game.get_height(), 2, ZOBRIST_SEED));
+        Ok(Self {
+            hash: zobrist.hash_board(game.get_board(), current_player),
+            game,
+            variant: Connect4Variant::default(),
+            zobrist,
+        })
+    }
+
+    /// Recomputes the Zobrist hash from the board
+    ///
+    /// `current_hash` returns the same value without scanning the board.
+    pub fn zobrist_hash(&self) -> u64 {
+        self.zobrist.hash_board(self.get_board(), self.get_current_player())
+    }
+
+    /// Gets the rule variant used for a full board
+    pub fn get_variant(&self) -> Connect4Variant {
+        self.variant
+    }
+
     /// Gets the number of pieces needed in a row to win
     ///
     /// # Returns
     /// The line size (typically 4 for standard Connect 4)
     pub fn get_line_size(&self) -> usize {
-        self.line_size
+        self.game.get_line_size()
     }
 
     /// Checks if a move is legal in the current game state
@@ -233,7 +264,7 @@ impl Connect4State {
     /// # Returns
     /// true if the move is legal, false otherwise
     pub fn is_legal(&self, mv: &Connect4Move) -> bool {
-        mv.0 < self.width && self.board[0][mv.0] == 0
+        mv.0 < self.game.get_width() && self.get_board()[0][mv.0] == 0
     }
 }
 
@@ -263,10 +294,151 @@ impl FromStr for Connect4Move {
     }
 }
 
+impl WireMove for Connect4Move {
+    /// One byte: the column
+    ///
+    /// Covers boards up to 256 columns wide; wider columns panic.
+    fn encode(&self) -> Vec<u8> {
+        let column = u8::try_from(self.0).expect("Connect4 wire moves cover columns 0-255");
+        vec![column]
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let [column] = fixed_bytes(bytes)?;
+        Ok(Connect4Move(column as usize))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_state_fingerprint() {
+        let play = |columns: &[usize]| {
+            let moves: Vec<Connect4Move> = columns.iter().map(|&c| Connect4Move(c)).collect();
+            Connect4State::from_moves(Connect4State::new(7, 6, 4), &moves).unwrap()
+        };
+
+        // Transpositions reach the same position
+        let a = play(&[3, 2, 4]);
+        let b = play(&[4, 2, 3]);
+        assert_eq!(a.state_fingerprint(), b.state_fingerprint());
+
+        // One move more, or the same pieces in swapped colours, is a different position
+        assert_ne!(a.state_fingerprint(), play(&[3, 2, 4, 4]).state_fingerprint());
+        assert_ne!(a.state_fingerprint(), play(&[2, 3, 4]).state_fingerprint());
+        assert_ne!(Connect4State::new(7, 6, 4).state_fingerprint(), play(&[0]).state_fingerprint());
+    }
+
+    #[test]
+    fn test_incremental_hash_matches_recomputation() {
+        let mut game = Connect4State::new(7, 6, 4);
+        assert_eq!(game.current_hash(), game.zobrist_hash());
+        for col in [3, 3, 4, 2, 6, 0, 3, 5] {
+            game.make_move(&Connect4Move(col));
+            assert_eq!(game.current_hash(), game.zobrist_hash());
+        }
+
+        // Transpositions hash alike, including games rebuilt from a board
+        let play = |columns: &[usize]| {
+            let moves: Vec<Connect4Move> = columns.iter().map(|&c| Connect4Move(c)).collect();
+            Connect4State::from_moves(Connect4State::new(7, 6, 4), &moves).unwrap()
+        };
+        let a = play(&[3, 2, 4]);
+        assert_eq!(a.current_hash(), play(&[4, 2, 3]).current_hash());
+        assert_ne!(a.current_hash(), play(&[2, 3, 4]).current_hash());
+        let rebuilt = Connect4State::from_board(a.get_board().clone(), -1, 4).unwrap();
+        assert_eq!(rebuilt.current_hash(), a.current_hash());
+    }
+
+    #[test]
+    fn test_tactical_moves_include_block() {
+        let mut game = Connect4State::new(7, 6, 4);
+        // Player 2 holds columns 3-5 of the bottom row, so player 1 must block column 2
+        for col in [0, 3, 0, 4, 6, 5] {
+            game.make_move(&Connect4Move(col));
+        }
+        let tactical = game.get_tactical_moves().unwrap();
+        assert!(tactical.contains(&Connect4Move(2)));
+        // Stacking a third piece on column 0 is a threat of player 1's own
+        assert!(tactical.contains(&Connect4Move(0)));
+        assert!(!tactical.contains(&Connect4Move(1)));
+    }
+
+    #[test]
+    fn test_feature_planes() {
+        let moves: Vec<_> = [3, 3, 4].into_iter().map(Connect4Move).collect();
+        let game = Connect4State::from_moves(Connect4State::new(7, 6, 4), &moves).unwrap();
+        let planes = game.feature_planes().unwrap();
+        assert_eq!(planes.len(), 4);
+        assert!(planes.iter().all(|plane| plane.len() == 6 && plane.iter().all(|row| row.len() == 7)));
+
+        // O to move: its single piece is "own", X's two pieces the opponent's
+        let count = |plane: &Vec<Vec<f32>>| plane.iter().flatten().sum::<f32>();
+        assert_eq!(planes[0][4][3], 1.0);
+        assert_eq!(count(&planes[0]), 1.0);
+        assert_eq!((planes[1][5][3], planes[1][5][4]), (1.0, 1.0));
+        assert_eq!(count(&planes[1]), 2.0);
+        assert_eq!(count(&planes[2]), 0.0);
+
+        // Only the last drop is marked
+        assert_eq!(planes[3][5][4], 1.0);
+        assert_eq!(count(&planes[3]), 1.0);
+    }
+
+    #[test]
+    fn test_wire_move_round_trip() {
+        for column in [0, 3, 6] {
+            let mv = Connect4Move(column);
+            assert_eq!(mv.encode().len(), 1);
+            assert_eq!(Connect4Move::decode(&mv.encode()), Ok(mv));
+        }
+        assert!(Connect4Move::decode(&[]).is_err());
+        assert_eq!(Connect4Move::decode(&Connect4Move(255).encode()), Ok(Connect4Move(255)));
+    }
+
+    #[test]
+    #[should_panic(expected = "columns 0-255")]
+    fn test_wire_move_rejects_wide_columns() {
+        Connect4Move(256).encode();
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "nonzero cell values")]
+    fn test_zobrist_rejects_empty_cell_value() {
+        ZobristTable::new(4, 2, ZOBRIST_SEED).piece_key(0, 0);
+    }
+
+    #[test]
+    fn test_from_board() {
+        let mut board = vec![vec![0; 7]; 6];
+        board[5][3] = 1;
+        board[4][3] = -1;
+        board[5][2] = 1;
+        let game = Connect4State::from_board(board.clone(), -1, 4).unwrap();
+        assert_eq!(game.get_current_player(), -1);
+        assert_eq!(game.get_board(), &board);
+        assert_eq!(game.get_possible_moves().len(), 7);
+        assert!(!game.is_terminal());
+
+        // A piece resting on an empty cell
+        let mut floating = board;
+        floating[3][0] = -1;
+        assert!(Connect4State::from_board(floating, 1, 4).unwrap_err().contains("Floating"));
+    }
+
+    #[test]
+    fn test_from_board_detects_finished_game() {
+        let mut board = vec![vec![0; 7]; 6];
+        board[5][..4].fill(1);
+        board[4][..3].fill(-1);
+        let game = Connect4State::from_board(board, -1, 4).unwrap();
+        assert!(game.is_terminal());
+        assert_eq!(game.get_winner(), Some(1));
+    }
+
     #[test]
     fn test_new_game() {
         let game = Connect4State::new(7, 6, 4);
@@ -287,6 +459,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_move_order_is_left_to_right() {
+        let mut game = Connect4State::new(4, 2, 3);
+        for col in [2, 2] {
+            game.make_move(&Connect4Move(col));
+        }
+        let expected: Vec<_> = [0, 1, 3].into_iter().map(Connect4Move).collect();
+        assert_eq!(game.get_possible_moves(), expected);
+    }
+
     #[test]
     fn test_make_move() {
         let mut game = Connect4State::new(7, 6, 4);
@@ -358,4 +540,34 @@ mod tests {
         assert_eq!(game.get_winner(), Some(1));
         assert!(game.is_terminal());
     }
+
+    #[test]
+    fn test_full_board_variants() {
+        // A 2x2 board can never hold a line of 3, so it always fills up
+        for (variant, expected) in [
+            (Connect4Variant::Draw, None),
+            (Connect4Variant::LastMoverWins, Some(-1)),
+        ] {
+            let mut game = Connect4State::with_variant(2, 2, 3, variant);
+            for col in [0, 1, 0, 1] {
+                game.make_move(&Connect4Move(col));
+            }
+            assert!(game.is_terminal());
+            assert_eq!(game.get_winner(), expected);
+        }
+    }
+
+    #[test]
+    fn test_from_moves() {
+        let moves: Vec<_> = [3, 3, 4].into_iter().map(Connect4Move).collect();
+        let game = Connect4State::from_moves(Connect4State::new(7, 6, 4), &moves).unwrap();
+        assert_eq!(game.get_board()[5][3], 1);
+        assert_eq!(game.get_board()[4][3], -1);
+        assert_eq!(game.get_board()[5][4], 1);
+        assert_eq!(game.get_current_player(), -1);
+
+        // Column 9 doesn't exist on a 7-wide board
+        let moves: Vec<_> = [3, 9, 4].into_iter().map(Connect4Move).collect();
+        assert_eq!(Connect4State::from_moves(Connect4State::new(7, 6, 4), &moves).err(), Some(1));
+    }
 }
@@ -11,6 +11,7 @@
 //! - Game is a draw if the board fills up with no winner
 
 use crate::GameState;
+use crate::games::connect::{ConnectMove, ConnectState, line_winner};
 use std::fmt;
 use std::str::FromStr;
 
@@ -21,44 +22,91 @@ use std::str::FromStr;
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct GomokuMove(pub usize, pub usize);
 
+/// Maps (row, col, last index) to the transformed (row, col) on a square board
+type SquareSymmetry = fn(usize, usize, usize) -> (usize, usize);
+
+/// The 8 rotations and reflections of a square board
+const SQUARE_SYMMETRIES: [SquareSymmetry; 8] = [
+    |r, c, _| (r, c),
+    |r, c, m| (c, m - r),
+    |r, c, m| (m - r, m - c),
+    |r, c, m| (m - c, r),
+    |r, c, m| (r, m - c),
+    |r, c, m| (m - r, c),
+    |r, c, _| (c, r),
+    |r, c, m| (m - c, m - r),
+];
+
 /// Represents the complete state of a Gomoku game
 ///
-/// Contains the board state, current player, game configuration, and move history.
+/// A square free-placement `ConnectState` holds the board, current player and
+/// last move; this type adds handicaps and board symmetries.
 /// The board uses 1 for player 1 pieces, -1 for player 2 pieces, and 0 for empty spaces.
 #[derive(Debug, Clone)]
 pub struct GomokuState {
-    /// The game board as a 2D vector
-    pub board: Vec<Vec<i32>>,
-    /// Current player (1 or -1)
-    pub current_player: i32,
-    /// Size of the board (NxN)
-    board_size: usize,
-    /// Number of pieces needed in a row to win
-    line_size: usize,
-    /// Last move made, if any
-    last_move: Option<(usize, usize)>,
+    /// The underlying connection game, always without gravity
+    game: ConnectState,
 }
 
 impl GomokuState {
     /// Creates a new Gomoku game with the specified configuration
     pub fn new(board_size: usize, line_size: usize) -> Self {
-        Self {
-            board: vec![vec![0; board_size]; board_size],
-            current_player: 1,
-            board_size,
-            line_size,
-            last_move: None,
+        Self { game: ConnectState::new(board_size, board_size, line_size, false) }
+    }
+
+    /// Creates a game from an arbitrary legal board
+    ///
+    /// The board must be square. It is rejected if the piece counts don't match
+    /// `current_player` or the player to move has a line. If the previous player
+    /// has a line, the game is over.
+    ///
+    /// # Arguments
+    /// * `board` - Rows of cells (1, -1 or 0)
+    /// * `current_player` - The player to move (1 or -1)
+    /// * `line_size` - Number of pieces needed in a row to win
+    pub fn from_board(board: Vec<Vec<i32>>, current_player: i32, line_size: usize) -> Result<Self, String> {
+        let game = ConnectState::from_board(board, current_player, line_size, false)?;
+        if game.get_width() != game.get_height() {
+            return Err("Gomoku board must be square".to_string());
         }
+        Ok(Self { game })
+    }
+
+    /// Creates a handicap game with pre-placed stones for player 1
+    ///
+    /// Player -1 moves first. Unlike `from_board`, the piece counts don't need to
+    /// match, so player 1 may start any number of stones ahead.
+    ///
+    /// # Arguments
+    /// * `board_size` - Size of the board (NxN)
+    /// * `line_size` - Number of pieces needed in a row to win
+    /// * `stones` - (row, col) of each handicap stone
+    pub fn with_handicap(board_size: usize, line_size: usize, stones: &[(usize, usize)]) -> Result<Self, String> {
+        let mut board = vec![vec![0; board_size]; board_size];
+        for &(r, c) in stones {
+            if r >= board_size || c >= board_size {
+                return Err(format!("Handicap stone ({}, {}) is off the board", r, c));
+            }
+            if board[r][c] != 0 {
+                return Err(format!("Duplicate handicap stone ({}, {})", r, c));
+            }
+            board[r][c] = 1;
+        }
+        if stones.iter().any(|&pos| line_winner(&board, pos, line_size).is_some()) {
+            return Err("Handicap stones already form a line".to_string());
+        }
+        let last_move = stones.last().copied();
+        Ok(Self { game: ConnectState::from_position(board, -1, line_size, false, last_move) })
     }
 
     /// Returns the board size (NxN)
     pub fn get_board_size(&self) -> usize {
-        self.board_size
+        self.game.get_width()
     }
 
     /// Returns the number of pieces needed in a row to win
     pub fn get_line_size(&self) -> usize {
-        self.line_size
+        self.game.get_line_size()
     }
 
     /// Checks if a move is legal in the current game state
@@ -71,24 +119,13 @@ impl GomokuState {
     /// # Returns
     /// True if the move is legal, false otherwise
     pub fn is_legal(&self, mv: &GomokuMove) -> bool {
-        mv.0 < self.board_size && mv.1 < self.board_size && self.board[mv.0][mv.1] == 0
+        self.game.is_legal(&ConnectMove(mv.0, mv.1))
     }
 }
 
 impl fmt::Display for GomokuState {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for row in &self.board {
-            for &cell in row {
-                let symbol = match cell {
-                    1 => "X",
-                    -1 => "O",
-                    _ => ".",
-                };
-                write!(f, "{} ", symbol)?;
-            }
-            writeln!(f)?;
-        }
-        Ok(())
+        fmt::Display::fmt(&self.game, f)
     }
 }
 
@@ -100,151 +137,92 @@ impl GameState for GomokuState {
     }
 
     fn get_board(&self) -> &Vec<Vec<i32>> {
-        &self.board
+        self.game.get_board()
     }
 
     fn get_last_move(&self) -> Option<Vec<(usize, usize)>> {
-        self.last_move.map(|(r, c)| vec![(r, c)])
+        self.game.get_last_move()
+    }
+
+    fn supports_gpu(&self) -> bool {
+        self.game.supports_gpu()
     }
 
     fn get_gpu_simulation_data(&self) -> Option<(Vec<i32>, usize, usize, i32)> {
-        let mut data = Vec::with_capacity(self.board_size * self.board_size);
-        // Normalize board so current player is always 1
-        // This allows batching states with different current players
-        let multiplier = if self.current_player == 1 { 1 } else { -1 };
-        for row in &self.board {
-            for &cell in row {
-                data.push(cell * multiplier);
-            }
-        }
-        Some((data, self.board_size, self.board_size, 1))
+        self.game.get_gpu_simulation_data()
     }
 
-    fn get_possible_moves(&self) -> Vec<Self::Move> {
-        (0..self.board_size)
-            .flat_map(|r| (0..self.board_size).map(move |c| (r, c)))
-            .filter(|&(r, c)| self.board[r][c] == 0)
-            .map(|(r, c)| GomokuMove(r, c))
-            .collect()
+    fn heuristic_value(&self) -> Option<f64> {
+        self.game.heuristic_value()
     }
 
-    fn make_move(&mut self, mv: &Self::Move) {
-        self.board[mv.0][mv.1] = self.current_player;
-        self.last_move = Some((mv.0, mv.1));
-        self.current_player = -self.current_player;
+    /// Prefers moves closer to the center of the board
+    fn tiebreak_value(&self, mv: &Self::Move) -> f64 {
+        let center = (self.get_board_size() as f64 - 1.0) / 2.0;
+        -((mv.0 as f64 - center).powi(2) + (mv.1 as f64 - center).powi(2))
     }
 
-    fn is_terminal(&self) -> bool {
-        self.get_winner().is_some() || self.get_possible_moves().is_empty()
+    /// Formats as "row,col", the notation parsed by `FromStr`
+    fn format_move(&self, mv: &Self::Move) -> String {
+        format!("{},{}", mv.0, mv.1)
     }
 
-    fn get_winner(&self) -> Option<i32> {
-        // If no move has been made yet, there's no winner
-        let last_move = self.last_move?;
-        let (r, c) = last_move;
-        let player = self.board[r][c];
+    /// Empty cells in row-major order
+    fn get_possible_moves(&self) -> Vec<Self::Move> {
+        self.game.empty_cells().map(|(r, c)| GomokuMove(r, c)).collect()
+    }
 
-        // If the position is empty, there's no winner (shouldn't happen in normal play)
-        if player == 0 {
+    fn get_canonical_moves(&self) -> Option<Vec<Self::Move>> {
+        let n = self.get_board_size();
+        let board = self.get_board();
+        let last = n.checked_sub(1)?;
+        let symmetries: Vec<_> = SQUARE_SYMMETRIES
+            .iter()
+            .filter(|map| {
+                (0..n).all(|r| {
+                    (0..n).all(|c| {
+                        let (tr, tc) = map(r, c, last);
+                        board[r][c] == board[tr][tc]
+                    })
+                })
+            })
+            .collect();
+        // Only the identity: every move is distinct
+        if symmetries.len() == 1 {
             return None;
         }
 
-        // Check horizontal (left-right through the last move)
-        let mut count = 1;
-        // Check left
-        for i in 1..self.line_size {
-            if c >= i && self.board[r][c - i] == player {
-                count += 1;
-            } else {
-                break;
+        let mut covered = vec![vec![false; n]; n];
+        let mut moves = Vec::new();
+        for r in 0..n {
+            for c in 0..n {
+                if board[r][c] != 0 || covered[r][c] {
+                    continue;
+                }
+                moves.push(GomokuMove(r, c));
+                for map in &symmetries {
+                    let (tr, tc) = map(r, c, last);
+                    covered[tr][tc] = true;
+                }
             }
         }
-        // Check right
-        for i in 1..self.line_size {
-            if c + i < self.board_size && self.board[r][c + i] == player {
-                count += 1;
-            } else {
-                break;
-            }
-        }
-        if count >= self.line_size {
-            return Some(player);
-        }
-
-        // Check vertical (up-down through the last move)
-        count = 1;
-        // Check up
-        for i in 1..self.line_size {
-            if r >= i && self.board[r - i][c] == player {
-                count += 1;
-            } else {
-                break;
-            }
-        }
-        // Check down
-        for i in 1..self.line_size {
-            if r + i < self.board_size && self.board[r + i][c] == player {
-                count += 1;
-            } else {
-                break;
-            }
-        }
-        if count >= self.line_size {
-            return Some(player);
-        }
+        Some(moves)
+    }
 
-        // Check diagonal (top-left to bottom-right through the last move)
-        count = 1;
-        // Check top-left
-        for i in 1..self.line_size {
-            if r >= i && c >= i && self.board[r - i][c - i] == player {
-                count += 1;
-            } else {
-                break;
-            }
-        }
-        // Check bottom-right
-        for i in 1..self.line_size {
-            if r + i < self.board_size
-                && c + i < self.board_size
-                && self.board[r + i][c + i] == player
-            {
-                count += 1;
-            } else {
-                break;
-            }
-        }
-        if count >= self.line_size {
-            return Some(player);
-        }
+    fn make_move(&mut self, mv: &Self::Move) {
+        self.game.place(mv.0, mv.1);
+    }
 
-        // Check diagonal (top-right to bottom-left through the last move)
-        count = 1;
-        // Check top-right
-        for i in 1..self.line_size {
-            if r >= i && c + i < self.board_size && self.board[r - i][c + i] == player {
-                count += 1;
-            } else {
-                break;
-            }
-        }
-        // Check bottom-left
-        for i in 1..self.line_size {
-            if r + i < self.board_size && c >= i && self.board[r + i][c - i] == player {
-                count += 1;
-            } else {
-                break;
-            }
-        }
-        if count >= self.line_size {
-            return Some(player);
-        }
+    fn is_terminal(&self) -> bool {
+        self.game.is_terminal()
+    }
 
-        None
+    fn get_winner(&self) -> Option<i32> {
+        self.game.get_winner()
     }
 
     fn get_current_player(&self) -> i32 {
-        self.current_player
+        self.game.get_current_player()
     }
 }
 
@@ -275,6 +253,64 @@ impl FromStr for GomokuMove {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_from_board() {
+        let mut board = vec![vec![0; 9]; 9];
+        board[4][4] = 1;
+        board[4][5] = -1;
+        let game = GomokuState::from_board(board.clone(), 1, 5).unwrap();
+        assert_eq!(game.get_current_player(), 1);
+        assert_eq!(game.get_possible_moves().len(), 79);
+
+        // Player 1 is to move by piece count
+        assert!(GomokuState::from_board(board, -1, 5).is_err());
+    }
+
+    #[test]
+    fn test_format_move_round_trips() {
+        let game = GomokuState::new(9, 5);
+        let text = game.format_move(&GomokuMove(3, 7));
+        assert_eq!(text, "3,7");
+        assert_eq!(text.parse::<GomokuMove>(), Ok(GomokuMove(3, 7)));
+    }
+
+    #[test]
+    fn test_handicap_start_favours_player_one() {
+        use crate::MCTS;
+
+        // Four stones on the centre row, open at both ends
+        let game = GomokuState::with_handicap(9, 5, &[(4, 2), (4, 3), (4, 4), (4, 5)]).unwrap();
+        assert_eq!(game.get_current_player(), -1);
+        assert!(GomokuState::with_handicap(9, 5, &[(4, 4), (4, 4)]).is_err());
+
+        let mut mcts = MCTS::new(1.4, 2, 100000);
+        let (best, _) = mcts.search(&game, 4000, 0, 0);
+        assert!(game.is_legal(&best));
+
+        // Root children hold player -1's results, so its expected score is poor
+        let (wins, visits) = mcts
+            .get_root_children_stats()
+            .values()
+            .fold((0.0, 0), |(w, v), &(wins, visits)| (w + wins, v + visits));
+        let score = wins / (2.0 * visits as f64);
+        assert!(score < 0.4, "player -1 scores {}", score);
+    }
+
+    #[test]
+    fn test_canonical_moves_reduce_symmetric_openings() {
+        let mut game = GomokuState::new(15, 5);
+        // One move per cell of the 1/8 triangle, centre line included
+        let canonical = game.get_canonical_moves().unwrap();
+        assert_eq!(canonical.len(), 36);
+        assert!(canonical.iter().all(|mv| game.is_legal(mv)));
+
+        // A centre stone keeps every symmetry; an off-axis reply breaks them all
+        game.make_move(&GomokuMove(7, 7));
+        assert_eq!(game.get_canonical_moves().unwrap().len(), 35);
+        game.make_move(&GomokuMove(2, 5));
+        assert!(game.get_canonical_moves().is_none());
+    }
+
     #[test]
     fn test_new_game() {
         let game = GomokuState::new(15, 5);
@@ -292,6 +328,18 @@ mod tests {
         assert_eq!(moves.len(), 15 * 15);
     }
 
+    #[test]
+    fn test_move_order_is_row_major() {
+        let mut game = GomokuState::new(3, 3);
+        game.make_move(&GomokuMove(1, 1));
+        game.make_move(&GomokuMove(0, 2));
+        let expected: Vec<_> = [(0, 0), (0, 1), (1, 0), (1, 2), (2, 0), (2, 1), (2, 2)]
+            .into_iter()
+            .map(|(r, c)| GomokuMove(r, c))
+            .collect();
+        assert_eq!(game.get_possible_moves(), expected);
+    }
+
     #[test]
     fn test_make_move() {
         let mut game = GomokuState::new(15, 5);
@@ -24,7 +24,7 @@
 //! Uses axial coordinates (q, r) for hexagonal grid representation.
 //! The hexagons use "pointy-top" orientation.
 
-use crate::GameState;
+use crate::{GameState, InputKind};
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt;
 
@@ -95,7 +95,9 @@ impl Piece {
 /// - ( 0, -1): Northwest
 /// - (+1, -1): Northeast
 /// - (-1, +1): Southwest
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+///
+/// Coordinates order by `q`, then `r`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct HexCoord {
     pub q: i32,
     pub r: i32,
@@ -284,7 +286,9 @@ impl HiveState {
         
         // Second move (first move of second player): must be adjacent to existing piece
         if self.turn == 2 {
-            return self.get_adjacent_empty_positions().into_iter().collect();
+            let mut positions: Vec<HexCoord> = self.get_adjacent_empty_positions().into_iter().collect();
+            positions.sort_unstable();
+            return positions;
         }
         
         // Normal placement: adjacent to own pieces, not adjacent to opponent pieces
@@ -310,6 +314,7 @@ impl HiveState {
             }
         }
         
+        valid.sort_unstable();
         valid
     }
 
@@ -571,6 +576,17 @@ impl HiveState {
         false
     }
 
+    /// Number of occupied hexes around a player's queen (0 if not placed)
+    fn queen_pressure(&self, player: i32) -> usize {
+        self.board
+            .iter()
+            .find(|(_, stack)| {
+                stack.iter().any(|p| p.player == player && p.piece_type == PieceType::Queen)
+            })
+            .map(|(coord, _)| coord.neighbors().iter().filter(|n| self.is_occupied(n)).count())
+            .unwrap_or(0)
+    }
+
     /// Get the line size (not applicable to Hive)
     pub fn get_line_size(&self) -> usize {
         1
@@ -807,6 +823,8 @@ impl GameState for HiveState {
         self.current_player
     }
 
+    /// Placements by piece type (in `PieceType::all` order), then target cell;
+    /// then movements by source cell, then target cell
     fn get_possible_moves(&self) -> Vec<Self::Move> {
         let mut moves = Vec::new();
         let player_idx = Self::player_index(self.current_player);
@@ -837,10 +855,14 @@ impl GameState for HiveState {
         
         // Movement moves (only if queen is placed)
         if self.queen_placed[player_idx] {
-            for coord in self.occupied_positions().cloned().collect::<Vec<_>>() {
+            let mut sources: Vec<HexCoord> = self.occupied_positions().cloned().collect();
+            sources.sort_unstable();
+            for coord in sources {
                 if let Some(top) = self.get_top_piece(&coord) {
                     if top.player == self.current_player {
-                        for dest in self.get_piece_moves(&coord) {
+                        let mut destinations = self.get_piece_moves(&coord);
+                        destinations.sort_unstable();
+                        for dest in destinations {
                             moves.push(HiveMove::Move {
                                 from: coord,
                                 to: dest,
@@ -924,6 +946,16 @@ impl GameState for HiveState {
     fn get_last_move(&self) -> Option<Vec<(usize, usize)>> {
         self.get_last_move()
     }
+
+    fn input_kind(&self) -> InputKind {
+        InputKind::DragMove
+    }
+
+    fn material_balance(&self) -> Option<f64> {
+        // Surround pressure: hexes closed around the opponent's queen minus around our own
+        let player = self.current_player;
+        Some(self.queen_pressure(-player) as f64 - self.queen_pressure(player) as f64)
+    }
 }
 
 #[cfg(test)]
@@ -946,6 +978,33 @@ mod tests {
         assert!(moves.len() >= 5);
     }
 
+    #[test]
+    fn test_move_order_is_stable() {
+        let play = || {
+            let mut state = HiveState::new();
+            for (piece_type, q) in [(PieceType::Queen, 0), (PieceType::Queen, 1), (PieceType::Ant, -1), (PieceType::Ant, 2)] {
+                state.make_move(&HiveMove::Place { piece_type, to: HexCoord::new(q, 0) });
+            }
+            state
+        };
+
+        // Independently built states hash their boards differently but agree
+        let moves = play().get_possible_moves();
+        assert_eq!(moves, play().get_possible_moves());
+
+        // Placements by piece type then cell, followed by movements by source then target
+        let key = |mv: &HiveMove| match mv {
+            HiveMove::Place { piece_type, to } => {
+                let kind = PieceType::all().iter().position(|p| p == piece_type).unwrap();
+                (0, kind, *to, *to)
+            }
+            HiveMove::Move { from, to } => (1, 0, *from, *to),
+            HiveMove::Pass => (2, 0, HexCoord::new(0, 0), HexCoord::new(0, 0)),
+        };
+        assert!(moves.iter().any(|mv| matches!(mv, HiveMove::Move { .. })));
+        assert!(moves.windows(2).all(|pair| key(&pair[0]) < key(&pair[1])));
+    }
+
     #[test]
     fn test_hex_neighbors() {
         let center = HexCoord::new(0, 0);
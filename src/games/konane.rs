@@ -0,0 +1,477 @@
+//! # Konane (Hawaiian Checkers) Game Implementation
+//!
+//! This module implements Konane, a two-player capture game played on a board
+//! that starts completely filled with alternating black and white stones.
+//!
+//! ## Rules
+//! - Black (player 1) opens by removing one of its stones from the center or a corner
+//! - White (player -1) then removes one of its stones next to the empty cell
+//! - Afterwards every move is a capture: a stone jumps orthogonally over an adjacent
+//!   enemy stone into the empty cell beyond, removing the jumped stone
+//! - A stone may keep jumping in the same straight line, stopping after any jump
+//! - A player who cannot move loses; there are no draws
+
+use crate::games::{DecodeError, WireMove, column_label, fixed_bytes};
+use crate::{GameState, MoveEffects};
+use std::fmt;
+use std::str::FromStr;
+
+/// The four orthogonal jump directions as (row, column) steps
+const DIRECTIONS: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+/// Represents a move in Konane
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum KonaneMove {
+    /// Opening move: take one of your own stones off the board at (row, col)
+    Remove(usize, usize),
+    /// Capture by jumping from one cell to another along a row or column
+    ///
+    /// Every second cell in between is an enemy stone that gets captured.
+    Jump { from: (usize, usize), to: (usize, usize) },
+}
+
+/// Represents the complete state of a Konane game
+///
+/// The board uses 1 for black stones, -1 for white stones, and 0 for empty cells.
+#[derive(Debug, Clone)]
+pub struct KonaneState {
+    /// The game board as a 2D vector (rows x columns)
+    board: Vec<Vec<i32>>,
+    /// Current player (1 for black, -1 for white)
+    current_player: i32,
+    /// Board width (number of columns)
+    width: usize,
+    /// Board height (number of rows)
+    height: usize,
+    /// Number of moves played so far (the first two are removals)
+    moves_played: usize,
+    /// Last move made, if any
+    last_move: Option<KonaneMove>,
+}
+
+impl fmt::Display for KonaneState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in &self.board {
+            for &cell in row {
+                let symbol = match cell {
+                    1 => "X",
+                    -1 => "O",
+                    _ => ".",
+                };
+                write!(f, "{} ", symbol)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl GameState for KonaneState {
+    type Move = KonaneMove;
+
+    fn get_num_players(&self) -> i32 {
+        2
+    }
+
+    fn get_board(&self) -> &Vec<Vec<i32>> {
+        &self.board
+    }
+
+    fn get_last_move(&self) -> Option<Vec<(usize, usize)>> {
+        self.last_move.map(|mv| match mv {
+            KonaneMove::Remove(r, c) => vec![(r, c)],
+            KonaneMove::Jump { from, to } => vec![from, to],
+        })
+    }
+
+    fn last_move_effects(&self) -> Option<MoveEffects> {
+        self.last_move.map(|mv| match mv {
+            KonaneMove::Remove(r, c) => MoveEffects { removed: vec![(r, c)], ..MoveEffects::default() },
+            KonaneMove::Jump { from, to } => {
+                let mut removed = vec![from];
+                removed.extend(captured_cells(from, to));
+                MoveEffects { placed: vec![to], flipped: Vec::new(), removed }
+            }
+        })
+    }
+
+    fn format_move(&self, mv: &Self::Move) -> String {
+        // Algebraic notation: column letter, then 1-based row
+        let cell = |(r, c): (usize, usize)| format!("{}{}", column_label(c), r + 1);
+        match *mv {
+            KonaneMove::Remove(r, c) => format!("x{}", cell((r, c))),
+            KonaneMove::Jump { from, to } => format!("{}-{}", cell(from), cell(to)),
+        }
+    }
+
+    /// Removals in row-major order (white's in direction order: up, down, left,
+    /// right); jumps by source cell in row-major order, then direction, then length
+    fn get_possible_moves(&self) -> Vec<Self::Move> {
+        match self.moves_played {
+            0 => self.first_removals(),
+            1 => self.second_removals(),
+            _ => self.jumps(),
+        }
+    }
+
+    fn make_move(&mut self, mv: &Self::Move) {
+        match *mv {
+            KonaneMove::Remove(r, c) => self.board[r][c] = 0,
+            KonaneMove::Jump { from, to } => {
+                for (r, c) in captured_cells(from, to) {
+                    self.board[r][c] = 0;
+                }
+                self.board[from.0][from.1] = 0;
+                self.board[to.0][to.1] = self.current_player;
+            }
+        }
+        self.last_move = Some(*mv);
+        self.moves_played += 1;
+        self.current_player = -self.current_player;
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.get_possible_moves().is_empty()
+    }
+
+    fn get_winner(&self) -> Option<i32> {
+        // The player who cannot move loses
+        if self.is_terminal() {
+            Some(-self.current_player)
+        } else {
+            None
+        }
+    }
+
+    fn get_current_player(&self) -> i32 {
+        self.current_player
+    }
+
+    fn material_balance(&self) -> Option<f64> {
+        // Mobility: in Konane running out of moves is what loses, not stone count
+        if self.moves_played < 2 {
+            return None;
+        }
+        let mut opponent = self.clone();
+        opponent.current_player = -self.current_player;
+        Some(self.jumps().len() as f64 - opponent.jumps().len() as f64)
+    }
+}
+
+impl KonaneState {
+    /// Creates a new Konane game with the specified board dimensions
+    ///
+    /// Fills the board with alternating stones, black in the top-left corner.
+    /// Black (player 1) moves first.
+    ///
+    /// # Arguments
+    /// * `width` - Number of columns
+    /// * `height` - Number of rows
+    pub fn new(width: usize, height: usize) -> Self {
+        assert!(width * height >= 2, "Board must have at least two cells.");
+        let board = (0..height)
+            .map(|r| (0..width).map(|c| if (r + c) % 2 == 0 { 1 } else { -1 }).collect())
+            .collect();
+        Self {
+            board,
+            current_player: 1,
+            width,
+            height,
+            moves_played: 0,
+            last_move: None,
+        }
+    }
+
+    /// Checks if a move is legal in the current game state
+    ///
+    /// # Arguments
+    /// * `mv` - The move to check
+    ///
+    /// # Returns
+    /// True if the move is legal, false otherwise
+    pub fn is_legal(&self, mv: &KonaneMove) -> bool {
+        self.get_possible_moves().contains(mv)
+    }
+
+    /// Black's opening removals: its stones in the central cells and the corners
+    fn first_removals(&self) -> Vec<KonaneMove> {
+        let (rows, cols) = (self.height - 1, self.width - 1);
+        let mut cells = vec![(0, 0), (0, cols), (rows, 0), (rows, cols)];
+        for r in [rows / 2, self.height / 2] {
+            for c in [cols / 2, self.width / 2] {
+                cells.push((r, c));
+            }
+        }
+        cells.sort_unstable();
+        cells.dedup();
+        cells
+            .into_iter()
+            .filter(|&(r, c)| self.board[r][c] == self.current_player)
+            .map(|(r, c)| KonaneMove::Remove(r, c))
+            .collect()
+    }
+
+    /// White's opening removals: its stones next to the cell black emptied
+    fn second_removals(&self) -> Vec<KonaneMove> {
+        let Some(KonaneMove::Remove(r, c)) = self.last_move else {
+            return Vec::new();
+        };
+        DIRECTIONS
+            .iter()
+            .filter_map(|&step| self.offset((r, c), step, 1))
+            .filter(|&(nr, nc)| self.board[nr][nc] == self.current_player)
+            .map(|(nr, nc)| KonaneMove::Remove(nr, nc))
+            .collect()
+    }
+
+    /// Every capture available to the current player, one move per landing cell
+    fn jumps(&self) -> Vec<KonaneMove> {
+        let mut moves = Vec::new();
+        for r in 0..self.height {
+            for c in 0..self.width {
+                if self.board[r][c] != self.current_player {
+                    continue;
+                }
+                for &step in &DIRECTIONS {
+                    // Each further jump lands two cells past the previous landing cell
+                    let mut distance = 2;
+                    while let (Some((jr, jc)), Some((lr, lc))) =
+                        (self.offset((r, c), step, distance - 1), self.offset((r, c), step, distance))
+                    {
+                        if self.board[jr][jc] != -self.current_player || self.board[lr][lc] != 0 {
+                            break;
+                        }
+                        moves.push(KonaneMove::Jump { from: (r, c), to: (lr, lc) });
+                        distance += 2;
+                    }
+                }
+            }
+        }
+        moves
+    }
+
+    /// The cell `distance` steps from `(r, c)`, if it is on the board
+    fn offset(&self, (r, c): (usize, usize), (dr, dc): (isize, isize), distance: usize) -> Option<(usize, usize)> {
+        let nr = r.checked_add_signed(dr * distance as isize)?;
+        let nc = c.checked_add_signed(dc * distance as isize)?;
+        (nr < self.height && nc < self.width).then_some((nr, nc))
+    }
+}
+
+/// The cells a jump from `from` to `to` passes over, which hold the captured stones
+fn captured_cells(from: (usize, usize), to: (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+    let step = |a: usize, b: usize| (b as isize - a as isize).signum();
+    let (dr, dc) = (step(from.0, to.0), step(from.1, to.1));
+    let distance = from.0.abs_diff(to.0).max(from.1.abs_diff(to.1));
+    (1..distance).step_by(2).map(move |i| {
+        (
+            (from.0 as isize + dr * i as isize) as usize,
+            (from.1 as isize + dc * i as isize) as usize,
+        )
+    })
+}
+
+impl FromStr for KonaneMove {
+    type Err = String;
+
+    /// Creates a KonaneMove from a string representation
+    ///
+    /// Expected format is "r,c" for a removal and "r,c-r,c" for a jump,
+    /// with 0-based indices.
+    ///
+    /// # Arguments
+    /// * `s` - String such as "3,3" or "3,1-3,5"
+    ///
+    /// # Returns
+    /// Ok(KonaneMove) if parsing succeeds, Err(String) if format is invalid
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let cell = |part: &str| -> Result<(usize, usize), String> {
+            let (r, c) = part.split_once(',').ok_or("Expected format: r,c or r,c-r,c")?;
+            let r = r.trim().parse::<usize>().map_err(|e| e.to_string())?;
+            let c = c.trim().parse::<usize>().map_err(|e| e.to_string())?;
+            Ok((r, c))
+        };
+        match s.split_once('-') {
+            Some((from, to)) => Ok(KonaneMove::Jump { from: cell(from)?, to: cell(to)? }),
+            None => cell(s).map(|(r, c)| KonaneMove::Remove(r, c)),
+        }
+    }
+}
+
+impl WireMove for KonaneMove {
+    /// Four bytes: from row and column, then to row and column
+    ///
+    /// A removal is encoded as a jump onto its own cell. Covers boards up to
+    /// 256x256; larger coordinates panic.
+    fn encode(&self) -> Vec<u8> {
+        let ((fr, fc), (tr, tc)) = match *self {
+            KonaneMove::Remove(r, c) => ((r, c), (r, c)),
+            KonaneMove::Jump { from, to } => (from, to),
+        };
+        [fr, fc, tr, tc]
+            .map(|coord| u8::try_from(coord).expect("Konane wire moves cover boards up to 256x256"))
+            .to_vec()
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let [fr, fc, tr, tc] = fixed_bytes(bytes)?.map(usize::from);
+        if (fr, fc) == (tr, tc) {
+            Ok(KonaneMove::Remove(fr, fc))
+        } else {
+            Ok(KonaneMove::Jump { from: (fr, fc), to: (tr, tc) })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a game past the opening from a board, with `player` to move
+    fn position(rows: &[&str], player: i32) -> KonaneState {
+        let board: Vec<Vec<i32>> = rows
+            .iter()
+            .map(|row| {
+                row.chars()
+                    .map(|ch| match ch {
+                        'X' => 1,
+                        'O' => -1,
+                        _ => 0,
+                    })
+                    .collect()
+            })
+            .collect();
+        KonaneState {
+            width: board[0].len(),
+            height: board.len(),
+            board,
+            current_player: player,
+            moves_played: 2,
+            last_move: None,
+        }
+    }
+
+    #[test]
+    fn test_new_game() {
+        let game = KonaneState::new(6, 6);
+        assert_eq!(game.get_num_players(), 2);
+        assert_eq!(game.get_current_player(), 1);
+        assert_eq!(game.get_board()[0][0], 1);
+        assert_eq!(game.get_board()[0][1], -1);
+        assert!(game.get_board().iter().flatten().all(|&cell| cell != 0));
+        assert!(!game.is_terminal());
+    }
+
+    #[test]
+    fn test_opening_removals() {
+        let mut game = KonaneState::new(6, 6);
+        let first = game.get_possible_moves();
+        // Two black center cells and two black corners
+        assert_eq!(first.len(), 4);
+        for mv in [KonaneMove::Remove(0, 0), KonaneMove::Remove(2, 2), KonaneMove::Remove(3, 3), KonaneMove::Remove(5, 5)] {
+            assert!(first.contains(&mv), "{:?}", mv);
+        }
+
+        // White answers next to the hole
+        game.make_move(&KonaneMove::Remove(0, 0));
+        let second = game.get_possible_moves();
+        assert_eq!(second.len(), 2);
+        assert!(second.contains(&KonaneMove::Remove(0, 1)));
+        assert!(second.contains(&KonaneMove::Remove(1, 0)));
+
+        // Then the first captures
+        game.make_move(&KonaneMove::Remove(0, 1));
+        assert_eq!(game.get_possible_moves(), vec![KonaneMove::Jump { from: (2, 0), to: (0, 0) }]);
+    }
+
+    #[test]
+    fn test_move_order() {
+        let game = KonaneState::new(6, 6);
+        let removals: Vec<_> = [(0, 0), (2, 2), (3, 3), (5, 5)]
+            .into_iter()
+            .map(|(r, c)| KonaneMove::Remove(r, c))
+            .collect();
+        assert_eq!(game.get_possible_moves(), removals);
+
+        // Jumps by source cell, then up, down, left, right, then length
+        let game = position(&[".....", "..O..", "XOXO.", "..O..", "....."], 1);
+        assert_eq!(
+            game.get_possible_moves(),
+            vec![
+                KonaneMove::Jump { from: (2, 2), to: (0, 2) },
+                KonaneMove::Jump { from: (2, 2), to: (4, 2) },
+                KonaneMove::Jump { from: (2, 2), to: (2, 4) },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_capture_jump_generation() {
+        let game = position(&["....", ".XO.", "....", ".O..", ".X.."], 1);
+        let moves = game.get_possible_moves();
+        assert_eq!(moves.len(), 2);
+        assert!(moves.contains(&KonaneMove::Jump { from: (1, 1), to: (1, 3) }));
+        assert!(moves.contains(&KonaneMove::Jump { from: (4, 1), to: (2, 1) }));
+
+        let mut next = game.clone();
+        next.make_move(&KonaneMove::Jump { from: (1, 1), to: (1, 3) });
+        assert_eq!(next.get_board()[1], vec![0, 0, 0, 1]);
+        assert_eq!(next.get_current_player(), -1);
+        let effects = next.last_move_effects().unwrap();
+        assert_eq!(effects.placed, vec![(1, 3)]);
+        assert_eq!(effects.removed, vec![(1, 1), (1, 2)]);
+    }
+
+    #[test]
+    fn test_multi_jump_chaining() {
+        let mut game = position(&["XO.O.O.", "......."], 1);
+        let moves = game.get_possible_moves();
+        // Stopping after any jump is a separate move
+        assert_eq!(moves.len(), 3);
+        for to in [2, 4, 6] {
+            assert!(moves.contains(&KonaneMove::Jump { from: (0, 0), to: (0, to) }));
+        }
+
+        game.make_move(&KonaneMove::Jump { from: (0, 0), to: (0, 4) });
+        assert_eq!(game.get_board()[0], vec![0, 0, 0, 0, 1, -1, 0]);
+
+        // A chain ends at an occupied landing cell
+        let blocked = position(&["XO.OO.."], 1);
+        assert_eq!(blocked.get_possible_moves(), vec![KonaneMove::Jump { from: (0, 0), to: (0, 2) }]);
+    }
+
+    #[test]
+    fn test_player_without_moves_loses() {
+        let mut game = position(&["XO.", "..."], 1);
+        assert!(!game.is_terminal());
+        assert_eq!(game.get_winner(), None);
+
+        game.make_move(&KonaneMove::Jump { from: (0, 0), to: (0, 2) });
+        assert!(game.get_possible_moves().is_empty());
+        assert!(game.is_terminal());
+        assert_eq!(game.get_winner(), Some(1));
+
+        // White to move with stones left but nothing to jump
+        let stuck = position(&["O.X", "X.O"], -1);
+        assert!(stuck.is_terminal());
+        assert_eq!(stuck.get_winner(), Some(1));
+    }
+
+    #[test]
+    fn test_move_round_trips() {
+        let moves = [KonaneMove::Remove(3, 3), KonaneMove::Jump { from: (1, 1), to: (1, 5) }];
+        for mv in moves {
+            assert_eq!(KonaneMove::decode(&mv.encode()), Ok(mv));
+        }
+        assert_eq!("3,3".parse(), Ok(KonaneMove::Remove(3, 3)));
+        assert_eq!("1,1-1,5".parse(), Ok(KonaneMove::Jump { from: (1, 1), to: (1, 5) }));
+        assert!("1;1".parse::<KonaneMove>().is_err());
+    }
+
+    #[test]
+    fn test_format_move_labels_wide_boards() {
+        let game = KonaneState::new(30, 2);
+        assert_eq!(game.format_move(&KonaneMove::Remove(0, 0)), "xA1");
+        assert_eq!(game.format_move(&KonaneMove::Jump { from: (1, 25), to: (1, 27) }), "Z2-AB2");
+    }
+}
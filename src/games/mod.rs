@@ -9,6 +9,8 @@
 //! - **Connect 4**: Gravity-based connection game on a 6x7 grid for 2 players  
 //! - **Blokus**: Polyomino tile-laying strategy game for 2-4 players on a 20x20 board
 //! - **Gomoku (Five in a Row)**: Configurable N-in-a-row game on variable board sizes
+//! - **Connect N**: Generic N-in-a-row game with or without gravity, shared by Connect 4 and Gomoku
+//! - **Konane (Hawaiian Checkers)**: Capture-by-jumping game where the player left without a move loses
 //!
 //! ## Game Trait Implementation
 //! All games implement the `mcts::GameState` trait which provides:
@@ -25,7 +27,222 @@
 //! 4. Game-specific rules and win conditions
 
 pub mod blokus;
+pub mod connect;
 pub mod connect4;
 pub mod gomoku;
 pub mod hive;
+pub mod konane;
 pub mod othello;
+
+use rand_xoshiro::Xoshiro256PlusPlus;
+use rand_xoshiro::rand_core::{RngCore, SeedableRng};
+use std::fmt;
+
+/// Compact binary encoding of a move for sending it over the network
+///
+/// Smaller and less fragile than exchanging `{:?}` strings. `decode` must
+/// accept exactly what `encode` produces.
+pub trait WireMove: Sized {
+    /// Encodes the move into bytes
+    fn encode(&self) -> Vec<u8>;
+
+    /// Decodes a move produced by `encode`
+    fn decode(bytes: &[u8]) -> Result<Self, DecodeError>;
+}
+
+/// Error returned when bytes don't decode to a move
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The encoding has the wrong number of bytes
+    Length { expected: usize, found: usize },
+    /// A byte is out of range for the move type
+    InvalidByte(u8),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::Length { expected, found } => {
+                write!(f, "Expected {} bytes, found {}", expected, found)
+            }
+            DecodeError::InvalidByte(byte) => write!(f, "Invalid byte {:#04x}", byte),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Random keys for Zobrist hashing a grid board
+///
+/// A position's hash XORs one key per occupied cell and one for the player to
+/// move, so `make_move` can update it by XORing the changed keys in and out.
+/// Pieces and players are numbered by zigzag on their cell value (1 -> 0,
+/// -1 -> 1, 2 -> 2, -2 -> 3, ...). Tables built with the same arguments hold
+/// the same keys.
+#[derive(Clone)]
+pub struct ZobristTable {
+    /// One key per (cell, piece), then one per player to move
+    keys: Vec<u64>,
+    cells: usize,
+    pieces: usize,
+}
+
+impl ZobristTable {
+    /// Generates keys for a board of `cells` cells and `pieces` piece kinds
+    pub fn new(cells: usize, pieces: usize, seed: u64) -> Self {
+        let mut rng = Xoshiro256PlusPlus::seed_from_u64(seed);
+        let keys = (0..(cells + 1) * pieces).map(|_| rng.next_u64()).collect();
+        Self { keys, cells, pieces }
+    }
+
+    /// Key for a piece with cell value `piece` on cell `cell`
+    ///
+    /// `cell` must be below the table's cell count and `piece` a nonzero value
+    /// within its piece kinds.
+    pub fn piece_key(&self, cell: usize, piece: i32) -> u64 {
+        debug_assert!(cell < self.cells, "cell {} out of range for {} cells", cell, self.cells);
+        self.keys[cell * self.pieces + self.index(piece)]
+    }
+
+    /// Key for `player` being the one to move
+    ///
+    /// `player` must be a nonzero value within the table's piece kinds.
+    pub fn player_key(&self, player: i32) -> u64 {
+        self.keys[self.cells * self.pieces + self.index(player)]
+    }
+
+    /// Hashes a board from scratch, for initializing or checking an incremental hash
+    pub fn hash_board(&self, board: &[Vec<i32>], current_player: i32) -> u64 {
+        let width = board.first().map_or(0, Vec::len);
+        let mut hash = self.player_key(current_player);
+        for (r, row) in board.iter().enumerate() {
+            for (c, &cell) in row.iter().enumerate() {
+                if cell != 0 {
+                    hash ^= self.piece_key(r * width + c, cell);
+                }
+            }
+        }
+        hash
+    }
+
+    fn index(&self, value: i32) -> usize {
+        debug_assert!(value != 0, "Zobrist keys are only defined for nonzero cell values");
+        let index = if value > 0 { 2 * (value - 1) as usize } else { 2 * (-value) as usize - 1 };
+        debug_assert!(index < self.pieces, "cell value {} out of range for {} piece kinds", value, self.pieces);
+        index
+    }
+}
+
+impl fmt::Debug for ZobristTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ZobristTable")
+            .field("cells", &self.cells)
+            .field("pieces", &self.pieces)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Encodes a two-player board as the four `GameState::feature_planes` channels
+pub(crate) fn two_player_planes(
+    board: &[Vec<i32>],
+    current_player: i32,
+    last_move: &[(usize, usize)],
+) -> Vec<Vec<Vec<f32>>> {
+    let plane = |value: &dyn Fn(i32) -> bool| -> Vec<Vec<f32>> {
+        board
+            .iter()
+            .map(|row| row.iter().map(|&cell| if value(cell) { 1.0 } else { 0.0 }).collect())
+            .collect()
+    };
+    let mut last = plane(&|_| false);
+    for &(r, c) in last_move {
+        last[r][c] = 1.0;
+    }
+    vec![
+        plane(&|cell| cell == current_player),
+        plane(&|cell| cell == -current_player),
+        plane(&|_| current_player == 1),
+        last,
+    ]
+}
+
+/// Labels a 0-based board column with letters: A..Z, then AA, AB, ...
+pub(crate) fn column_label(col: usize) -> String {
+    let mut label = Vec::new();
+    let mut n = col + 1;
+    while n > 0 {
+        n -= 1;
+        label.push(b'A' + (n % 26) as u8);
+        n /= 26;
+    }
+    label.iter().rev().map(|&b| b as char).collect()
+}
+
+/// Checks that an encoding has exactly `N` bytes
+pub(crate) fn fixed_bytes<const N: usize>(bytes: &[u8]) -> Result<[u8; N], DecodeError> {
+    bytes
+        .try_into()
+        .map_err(|_| DecodeError::Length { expected: N, found: bytes.len() })
+}
+
+/// Checks a custom board for a two-player stone game where player 1 moves first
+///
+/// Cells must be 1, -1 or 0, the board must be a non-empty rectangle, and the
+/// stone counts must match the player to move.
+pub(crate) fn validate_two_player_board(board: &[Vec<i32>], current_player: i32) -> Result<(), String> {
+    let width = board.first().map_or(0, Vec::len);
+    if width == 0 || board.iter().any(|row| row.len() != width) {
+        return Err("Board must be a non-empty rectangle".to_string());
+    }
+    if current_player != 1 && current_player != -1 {
+        return Err(format!("Invalid current player {}, expected 1 or -1", current_player));
+    }
+    if let Some(cell) = board.iter().flatten().find(|&&cell| !(-1..=1).contains(&cell)) {
+        return Err(format!("Invalid cell value {}, expected 1, -1 or 0", cell));
+    }
+
+    let first = board.iter().flatten().filter(|&&cell| cell == 1).count();
+    let second = board.iter().flatten().filter(|&&cell| cell == -1).count();
+    let expected_player = match first.checked_sub(second) {
+        Some(0) => 1,
+        Some(1) => -1,
+        _ => return Err(format!("Impossible piece counts: {} for player 1, {} for player -1", first, second)),
+    };
+    if current_player != expected_player {
+        return Err(format!("Piece counts imply player {} is to move", expected_player));
+    }
+    Ok(())
+}
+
+/// Picks the last move of a board built from scratch, for games that detect wins
+/// only through the last move
+///
+/// A completed line of the previous player must contain the last move, so a
+/// piece on such a line is preferred.
+///
+/// # Arguments
+/// * `board` - Rows of cells (1, -1 or 0)
+/// * `current_player` - The player to move (1 or -1)
+/// * `winner_at` - Returns the owner of a winning line through a cell, if any
+///
+/// # Returns
+/// The last move (None on an empty board), or an error if the player to move
+/// already has a line
+pub(crate) fn derive_last_move(
+    board: &[Vec<i32>],
+    current_player: i32,
+    winner_at: impl Fn((usize, usize)) -> Option<i32>,
+) -> Result<Option<(usize, usize)>, String> {
+    let cells = |player: i32| {
+        board.iter().enumerate().flat_map(move |(r, row)| {
+            row.iter().enumerate().filter(move |&(_, &cell)| cell == player).map(move |(c, _)| (r, c))
+        })
+    };
+
+    if cells(current_player).any(|pos| winner_at(pos).is_some()) {
+        return Err("The player to move already has a line".to_string());
+    }
+    Ok(cells(-current_player)
+        .find(|&pos| winner_at(pos).is_some())
+        .or_else(|| cells(-current_player).last()))
+}
@@ -12,10 +12,23 @@
 //! - Game ends when neither player can make a move
 //! - Winner is determined by who has more pieces on the board
 
-use crate::GameState;
+use crate::games::{DecodeError, WireMove, column_label, fixed_bytes, two_player_planes};
+use crate::{GamePhase, GameState, MoveEffects};
 use std::fmt;
 use std::str::FromStr;
 
+/// The 8 directions along which discs can be flipped
+const DIRECTIONS: [(i32, i32); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
 /// Represents a move in Othello
 ///
 /// Contains the row and column coordinates where a player wants to place their piece.
@@ -37,6 +50,9 @@ pub struct OthelloState {
     board_size: usize,
     /// Last move made, if any
     last_move: Option<(usize, usize)>,
+    /// Discs flipped by the last move along each of `DIRECTIONS`, counted outward
+    /// from the placed disc (kept inline so rollouts and clones don't allocate)
+    last_flips: [u8; 8],
 }
 
 impl fmt::Display for OthelloState {
@@ -67,6 +83,7 @@ impl GameState for OthelloState {
         &self.board
     }
 
+    /// Legal cells in row-major order
     fn get_possible_moves(&self) -> Vec<Self::Move> {
         let mut moves = Vec::new();
         for r in 0..self.board_size {
@@ -108,6 +125,39 @@ impl GameState for OthelloState {
         self.last_move.map(|(r, c)| vec![(r, c)])
     }
 
+    fn last_move_effects(&self) -> Option<MoveEffects> {
+        self.last_move.map(|pos| MoveEffects {
+            placed: vec![pos],
+            flipped: self.last_flipped(),
+            removed: Vec::new(),
+        })
+    }
+
+    /// Compares only the rows that differ, since a move leaves most rows untouched
+    fn board_diff(&self, prev: &Self) -> Vec<(usize, usize, i32)> {
+        let mut diff = Vec::new();
+        for (r, (row, prev_row)) in self.board.iter().zip(&prev.board).enumerate() {
+            if row == prev_row {
+                continue;
+            }
+            for (c, (&cell, &prev_cell)) in row.iter().zip(prev_row).enumerate() {
+                if cell != prev_cell {
+                    diff.push((r, c, cell));
+                }
+            }
+        }
+        diff
+    }
+
+    fn format_move(&self, mv: &Self::Move) -> String {
+        // Algebraic notation: column letter, then 1-based row
+        format!("{}{}", column_label(mv.1), mv.0 + 1)
+    }
+
+    fn supports_gpu(&self) -> bool {
+        true
+    }
+
     fn get_gpu_simulation_data(&self) -> Option<(Vec<i32>, usize, usize, i32)> {
         let mut data = Vec::with_capacity(self.board_size * self.board_size);
         // Normalize board so current player is always 1
@@ -124,6 +174,22 @@ impl GameState for OthelloState {
         Some((data, self.board_size, self.board_size, encoded_params))
     }
 
+    fn get_gpu_legal_mask(&self) -> Option<Vec<u32>> {
+        let mut mask = vec![0u32; (self.board_size * self.board_size).div_ceil(32)];
+        for OthelloMove(r, c) in self.get_possible_moves() {
+            let cell = r * self.board_size + c;
+            mask[cell / 32] |= 1 << (cell % 32);
+        }
+        Some(mask)
+    }
+
+    fn feature_planes(&self) -> Option<Vec<Vec<Vec<f32>>>> {
+        // The last move marks the placed disc and every disc it flipped
+        let mut last_move: Vec<_> = self.last_move.into_iter().collect();
+        last_move.extend(self.last_flipped());
+        Some(two_player_planes(&self.board, self.current_player, &last_move))
+    }
+
     fn get_winner(&self) -> Option<i32> {
         if !self.is_terminal() {
             return None;
@@ -153,13 +219,24 @@ impl GameState for OthelloState {
     fn get_current_player(&self) -> i32 {
         self.current_player
     }
+
+    fn game_phase(&self) -> GamePhase {
+        GamePhase::from_fill_ratio(self.disc_count(), self.board_size * self.board_size)
+    }
+
+    fn material_balance(&self) -> Option<f64> {
+        // Disc differential; the board holds +1/-1 per disc
+        let balance: i32 = self.board.iter().flatten().sum();
+        Some((balance * self.current_player) as f64)
+    }
 }
 
 impl OthelloState {
     /// Creates a new Othello game with the specified board size
     ///
     /// Sets up the board with 4 pieces in the center in the traditional pattern.
-    /// Black (player 1) starts first.
+    /// Black (player 1) starts first. The size must be even and at most 256 so
+    /// every move fits the wire encoding.
     ///
     /// # Returns
     /// A new OthelloState ready to play
@@ -168,6 +245,7 @@ impl OthelloState {
             board_size > 0 && board_size % 2 == 0,
             "Board size must be a positive even number."
         );
+        assert!(board_size <= 256, "Board size must be at most 256.");
         let mut board = vec![vec![0; board_size]; board_size];
         let center = board_size / 2;
         board[center - 1][center - 1] = -1;
@@ -180,6 +258,7 @@ impl OthelloState {
             current_player: 1,
             board_size: board_size,
             last_move: None,
+            last_flips: [0; 8],
         }
     }
 
@@ -204,6 +283,25 @@ impl OthelloState {
         self.is_valid_move((mv.0, mv.1))
     }
 
+    /// Returns the discs flipped by the last move
+    fn last_flipped(&self) -> Vec<(usize, usize)> {
+        let Some((r, c)) = self.last_move else {
+            return Vec::new();
+        };
+        DIRECTIONS
+            .iter()
+            .zip(self.last_flips)
+            .flat_map(|(&(dr, dc), run)| {
+                (1..=run as i32).map(move |i| ((r as i32 + dr * i) as usize, (c as i32 + dc * i) as usize))
+            })
+            .collect()
+    }
+
+    /// Counts the discs of both colors on the board
+    fn disc_count(&self) -> usize {
+        self.board.iter().flatten().filter(|&&cell| cell != 0).count()
+    }
+
     /// Internal helper to check if a move at given coordinates is valid
     ///
     /// Checks all 8 directions from the proposed move to see if any opponent
@@ -221,18 +319,7 @@ impl OthelloState {
         }
 
         let opponent = -self.current_player;
-        let directions = [
-            (-1, -1),
-            (-1, 0),
-            (-1, 1),
-            (0, -1),
-            (0, 1),
-            (1, -1),
-            (1, 0),
-            (1, 1),
-        ];
-
-        for (dr, dc) in directions.iter() {
+        for (dr, dc) in DIRECTIONS.iter() {
             let mut line = Vec::new();
             let mut nr = r as i32 + dr;
             let mut nc = c as i32 + dc;
@@ -266,18 +353,8 @@ impl OthelloState {
     /// * `c` - Column coordinate of the newly placed piece
     fn flip_pieces(&mut self, r: usize, c: usize) {
         let opponent = -self.current_player;
-        let directions = [
-            (-1, -1),
-            (-1, 0),
-            (-1, 1),
-            (0, -1),
-            (0, 1),
-            (1, -1),
-            (1, 0),
-            (1, 1),
-        ];
-
-        for (dr, dc) in directions.iter() {
+        self.last_flips = [0; 8];
+        for (d, (dr, dc)) in DIRECTIONS.iter().enumerate() {
             let mut line = Vec::new();
             let mut nr = r as i32 + dr;
             let mut nc = c as i32 + dc;
@@ -286,9 +363,10 @@ impl OthelloState {
                 if self.board[nr as usize][nc as usize] == opponent {
                     line.push((nr as usize, nc as usize));
                 } else if self.board[nr as usize][nc as usize] == self.current_player {
-                    for (fr, fc) in line {
+                    for &(fr, fc) in &line {
                         self.board[fr][fc] = self.current_player;
                     }
+                    self.last_flips[d] = line.len() as u8;
                     break;
                 } else {
                     break;
@@ -323,10 +401,80 @@ impl FromStr for OthelloMove {
     }
 }
 
+impl WireMove for OthelloMove {
+    /// Two bytes: row, then column
+    ///
+    /// Covers every board `OthelloState::new` accepts (up to 256x256). Passes are
+    /// implicit in this implementation, so there is no pass move to encode.
+    fn encode(&self) -> Vec<u8> {
+        let row = u8::try_from(self.0).expect("Othello wire moves cover boards up to 256x256");
+        let col = u8::try_from(self.1).expect("Othello wire moves cover boards up to 256x256");
+        vec![row, col]
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let [row, col] = fixed_bytes(bytes)?;
+        Ok(OthelloMove(row as usize, col as usize))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_wire_move_round_trip() {
+        for mv in [OthelloMove(0, 0), OthelloMove(3, 2), OthelloMove(7, 7), OthelloMove(255, 255)] {
+            assert_eq!(mv.encode().len(), 2);
+            assert_eq!(OthelloMove::decode(&mv.encode()), Ok(mv));
+        }
+        assert!(OthelloMove::decode(&[1]).is_err());
+    }
+
+    #[test]
+    fn test_wire_move_covers_largest_board() {
+        let game = OthelloState::new(256);
+        let moves = game.get_possible_moves();
+        assert!(!moves.is_empty());
+        for mv in moves {
+            assert_eq!(OthelloMove::decode(&mv.encode()), Ok(mv));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "at most 256")]
+    fn test_new_rejects_boards_past_wire_limit() {
+        OthelloState::new(258);
+    }
+
+    #[test]
+    fn test_format_move_is_algebraic() {
+        let game = OthelloState::new(8);
+        assert_eq!(game.format_move(&OthelloMove(3, 2)), "C4");
+        assert_eq!(game.format_move(&OthelloMove(0, 0)), "A1");
+        assert_eq!(game.format_move(&OthelloMove(7, 7)), "H8");
+        let large = OthelloState::new(256);
+        assert_eq!(large.format_move(&OthelloMove(0, 25)), "Z1");
+        assert_eq!(large.format_move(&OthelloMove(0, 26)), "AA1");
+        assert_eq!(large.format_move(&OthelloMove(255, 255)), "IV256");
+    }
+
+    #[test]
+    fn test_material_balance_sign() {
+        let mut game = OthelloState::new(8);
+        assert_eq!(game.material_balance(), Some(0.0));
+
+        // Black fills the board except one disc of white
+        for row in game.board.iter_mut() {
+            row.fill(1);
+        }
+        game.board[0][0] = -1;
+        game.current_player = 1;
+        assert_eq!(game.material_balance(), Some(62.0));
+        game.current_player = -1;
+        assert_eq!(game.material_balance(), Some(-62.0));
+    }
+
     #[test]
     fn test_new_game() {
         let game = OthelloState::new(8);
@@ -355,6 +503,35 @@ mod tests {
         assert!(moves.contains(&OthelloMove(5, 4)));
     }
 
+    #[test]
+    fn test_move_order_is_row_major() {
+        let mut game = OthelloState::new(8);
+        assert_eq!(
+            game.get_possible_moves(),
+            vec![OthelloMove(2, 3), OthelloMove(3, 2), OthelloMove(4, 5), OthelloMove(5, 4)]
+        );
+        game.make_move(&OthelloMove(2, 3));
+        assert_eq!(
+            game.get_possible_moves(),
+            vec![OthelloMove(2, 2), OthelloMove(2, 4), OthelloMove(4, 2)]
+        );
+    }
+
+    #[test]
+    fn test_gpu_legal_mask_matches_moves() {
+        let mut game = OthelloState::new(8);
+        for mv in [OthelloMove(2, 3), OthelloMove(2, 2), OthelloMove(3, 2)] {
+            game.make_move(&mv);
+            let mask = game.get_gpu_legal_mask().unwrap();
+            assert_eq!(mask.len(), 2);
+            let masked: Vec<_> = (0..64)
+                .filter(|&i| mask[i / 32] & (1 << (i % 32)) != 0)
+                .map(|i| OthelloMove(i / 8, i % 8))
+                .collect();
+            assert_eq!(masked, game.get_possible_moves());
+        }
+    }
+
     #[test]
     fn test_make_move() {
         let mut game = OthelloState::new(8);
@@ -367,4 +544,57 @@ mod tests {
         assert_eq!(board[3][3], 1); // Flipped piece
         assert_eq!(game.get_current_player(), -1);
     }
+
+    #[test]
+    fn test_last_move_effects_report_flips() {
+        let mut game = OthelloState::new(8);
+        assert_eq!(game.last_move_effects(), None);
+        game.make_move(&OthelloMove(2, 3));
+        game.make_move(&OthelloMove(2, 4));
+        // Black's (4,5) flips (3,4) diagonally and (4,4) horizontally
+        game.make_move(&OthelloMove(4, 5));
+
+        let mut effects = game.last_move_effects().unwrap();
+        effects.flipped.sort();
+        assert_eq!(effects.placed, vec![(4, 5)]);
+        assert_eq!(effects.flipped, vec![(3, 4), (4, 4)]);
+        assert!(effects.removed.is_empty());
+    }
+
+    #[test]
+    fn test_game_phase_progresses() {
+        let mut game = OthelloState::new(8);
+        assert_eq!(game.game_phase(), GamePhase::Opening);
+
+        let mut phases = vec![game.game_phase()];
+        while !game.is_terminal() {
+            let mv = game.get_possible_moves()[0].clone();
+            game.make_move(&mv);
+            phases.push(game.game_phase());
+        }
+        // Disc count only grows, so the phase never moves backwards
+        assert!(phases.windows(2).all(|w| w[0] <= w[1]));
+        assert!(phases.contains(&GamePhase::Midgame));
+        assert_eq!(*phases.last().unwrap(), GamePhase::Endgame);
+    }
+
+    #[test]
+    fn test_board_diff_after_move() {
+        let mut game = OthelloState::new(8);
+        game.make_move(&OthelloMove(2, 3));
+        game.make_move(&OthelloMove(2, 2));
+        let prev = game.clone();
+        // Black plays (4,5): flips (4,4) horizontally, nothing else changes
+        game.make_move(&OthelloMove(4, 5));
+
+        let mut diff = game.board_diff(&prev);
+        diff.sort();
+        assert_eq!(diff, vec![(4, 4, 1), (4, 5, 1)]);
+        // A position that isn't the predecessor still diffs exactly, off the move's rays too
+        let mut other = prev.clone();
+        other.board[2][2] = 1;
+        let mut diff = game.board_diff(&other);
+        diff.sort();
+        assert_eq!(diff, vec![(2, 2, -1), (4, 4, 1), (4, 5, 1)]);
+    }
 }
@@ -73,6 +73,26 @@ pub struct GpuSimulationParams {
     pub current_player: i32,  // Lower 8 bits: player, bits 8-15: line_size for Connect4
     pub use_heuristic: u32,
     pub seed: u32,
+    pub heuristic_weights: GpuHeuristicWeights,
+}
+
+/// Weights of the line patterns scored by the grid-game heuristic
+///
+/// Patterns are windows of the given length holding only one player's stones
+/// and empty cells: `near_win` for `line_size`, `threat` for `line_size - 1`
+/// and `build` for `line_size - 2`.
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable, Debug, PartialEq)]
+pub struct GpuHeuristicWeights {
+    pub near_win: f32,
+    pub threat: f32,
+    pub build: f32,
+}
+
+impl Default for GpuHeuristicWeights {
+    fn default() -> Self {
+        Self { near_win: 100.0, threat: 10.0, build: 1.0 }
+    }
 }
 
 #[repr(C)]
@@ -395,6 +415,12 @@ impl GpuMctsAccelerator {
         (self.total_gpu_time_us, self.dispatch_count, avg)
     }
 
+    /// Clears the dispatch count and accumulated GPU time
+    pub fn reset_stats(&mut self) {
+        self.total_gpu_time_us = 0;
+        self.dispatch_count = 0;
+    }
+
     pub fn debug_info(&self) -> String {
         let (total_us, dispatches, avg_us) = self.stats();
         format!("{}\nStats: {} dispatches, {:.2}ms total, {:.2}µs avg",
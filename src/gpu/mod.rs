@@ -18,7 +18,7 @@ mod accelerator;
 mod shaders;
 
 pub use context::GpuContext;
-pub use accelerator::{GpuMctsAccelerator, GpuNodeData, GpuPuctResult, GpuSimulationParams};
+pub use accelerator::{GpuHeuristicWeights, GpuMctsAccelerator, GpuNodeData, GpuPuctResult, GpuSimulationParams};
 
 /// Configuration for GPU acceleration
 #[derive(Debug, Clone)]
@@ -31,6 +31,22 @@ pub struct GpuConfig {
     pub min_batch_threshold: usize,
     /// Enable debug output for GPU operations
     pub debug_mode: bool,
+    /// How the batching worker waits for more requests while filling a batch
+    pub idle_strategy: GpuIdleStrategy,
+    /// Pattern weights of the grid-game heuristic evaluation
+    pub heuristic_weights: GpuHeuristicWeights,
+    /// Most simulations queued for the GPU at once; further ones run on the CPU
+    pub max_pending_evaluations: usize,
+}
+
+/// How the GPU batching worker waits for requests while filling a batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GpuIdleStrategy {
+    /// Busy-wait with `yield_now` (lowest latency, keeps a core busy)
+    #[default]
+    SpinYield,
+    /// Block on the request channel until a request arrives or the batch deadline passes
+    Block,
 }
 
 impl Default for GpuConfig {
@@ -40,10 +56,28 @@ impl Default for GpuConfig {
             prefer_high_performance: true,
             min_batch_threshold: 256, // Don't use GPU for less than 256 nodes
             debug_mode: false,
+            idle_strategy: GpuIdleStrategy::default(),
+            heuristic_weights: GpuHeuristicWeights::default(),
+            max_pending_evaluations: 10000,
         }
     }
 }
 
+/// Snapshot of the accelerator's dispatch counters, from `MCTS::gpu_stats`
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct GpuStats {
+    /// Number of compute dispatches since creation or the last reset
+    pub dispatches: u64,
+    /// Total time spent in dispatches, in microseconds
+    pub total_us: u64,
+    /// Average time per dispatch, in microseconds
+    pub avg_us: f64,
+    /// Number of nodes in the most recent PUCT batch
+    pub last_batch_size: usize,
+    /// Most simulations that were queued for the GPU at once
+    pub peak_pending: usize,
+}
+
 /// Result of GPU initialization
 #[derive(Debug)]
 pub enum GpuInitResult {
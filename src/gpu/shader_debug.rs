@@ -11,6 +11,7 @@ mod tests {
             prefer_high_performance: true,
             min_batch_threshold: 0, // Force GPU usage even for small batches
             debug_mode: true,
+            ..Default::default()
         };
 
         println!("Initializing GPU Context...");
@@ -77,6 +78,7 @@ mod tests {
             prefer_high_performance: true,
             min_batch_threshold: 0,
             debug_mode: true,
+            ..Default::default()
         };
 
         println!("Initializing GPU Context for Simulation...");
@@ -108,6 +110,7 @@ mod tests {
             current_player: 1 | (5 << 8), // Player 1, line_size 5 (though Gomoku shader might ignore line_size if hardcoded)
             use_heuristic: 0,
             seed: 12345,
+            heuristic_weights: Default::default(),
         };
 
         // 3. Run Simulation
@@ -131,6 +134,7 @@ mod tests {
             prefer_high_performance: true,
             min_batch_threshold: 0,
             debug_mode: true,
+            ..Default::default()
         };
 
         println!("Initializing GPU Context for Connect4...");
@@ -158,6 +162,7 @@ mod tests {
             current_player: 1 | (4 << 8) | (1 << 16), // Player 1, line_size 4, Game Type 1 (Connect4)
             use_heuristic: 0,
             seed: 12345,
+            heuristic_weights: Default::default(),
         };
 
         println!("\nRunning Connect4 Shader...");
@@ -178,6 +183,7 @@ mod tests {
             prefer_high_performance: true,
             min_batch_threshold: 0,
             debug_mode: true,
+            ..Default::default()
         };
 
         println!("Initializing GPU Context for Othello...");
@@ -211,6 +217,7 @@ mod tests {
             current_player: 1 | (2 << 16), // Player 1, Game Type 2 (Othello)
             use_heuristic: 0,
             seed: 12345,
+            heuristic_weights: Default::default(),
         };
 
         println!("\nRunning Othello Shader...");
@@ -231,6 +238,7 @@ mod tests {
             prefer_high_performance: true,
             min_batch_threshold: 0,
             debug_mode: true,
+            ..Default::default()
         };
 
         println!("Initializing GPU Context for Blokus...");
@@ -270,6 +278,7 @@ mod tests {
             current_player: 1 | (3 << 16), // Player 1, Game Type 3 (Blokus)
             use_heuristic: 0,
             seed: 12345,
+            heuristic_weights: Default::default(),
         };
 
         println!("\nRunning Blokus Shader...");
@@ -290,6 +299,7 @@ mod tests {
             prefer_high_performance: true,
             min_batch_threshold: 0,
             debug_mode: true,
+            ..Default::default()
         };
 
         println!("Initializing GPU Context for Hive...");
@@ -328,6 +338,7 @@ mod tests {
             current_player: 2 | (4 << 16), // Player 2, Game Type 4 (Hive)
             use_heuristic: 0,
             seed: 12345,
+            heuristic_weights: Default::default(),
         };
 
         println!("\nRunning Hive Shader...");
@@ -338,4 +349,49 @@ mod tests {
             println!("  Board {}: Score = {:.6}", i, score);
         }
     }
+
+    #[test]
+    fn heuristic_weights_change_grid_scores() {
+        use crate::gpu::{GpuHeuristicWeights, GpuSimulationParams};
+
+        let config = GpuConfig {
+            max_batch_size: 1024,
+            min_batch_threshold: 0,
+            ..Default::default()
+        };
+
+        let context = match GpuContext::new(&config) {
+            Ok(ctx) => Arc::new(ctx),
+            Err(e) => {
+                eprintln!("Failed to initialize GPU: {}", e);
+                return;
+            }
+        };
+
+        let mut accelerator = GpuMctsAccelerator::new(context.clone());
+
+        // Gomoku board with an open three for player 1 and a lone stone for player -1
+        let width = 15;
+        let mut board = vec![0; width * width];
+        board[7 * width + 6] = 1;
+        board[7 * width + 7] = 1;
+        board[7 * width + 8] = 1;
+        board[3 * width + 3] = -1;
+
+        let mut score_with = |heuristic_weights: GpuHeuristicWeights| {
+            let params = GpuSimulationParams {
+                board_width: width as u32,
+                board_height: width as u32,
+                current_player: 1 | (5 << 8), // Player 1, line_size 5, Game Type 0 (Gomoku)
+                use_heuristic: 1,
+                seed: 12345,
+                heuristic_weights,
+            };
+            accelerator.simulate_batch(&board, params).expect("Failed to simulate Gomoku")[0]
+        };
+
+        let default_score = score_with(GpuHeuristicWeights::default());
+        let build_heavy = score_with(GpuHeuristicWeights { near_win: 100.0, threat: 10.0, build: 50.0 });
+        assert_ne!(default_score, build_heavy);
+    }
 }
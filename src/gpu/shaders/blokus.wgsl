@@ -160,7 +160,11 @@ fn blokus_random_rollout(board_idx: u32, start_player: i32) -> f32 {
 @compute @workgroup_size(64)
 fn evaluate_blokus(@builtin(global_invocation_id) global_id: vec3<u32>) {
     let idx = global_id.x;
-    let current_player = 1;
+    // The player to move is stored per board in the state row; boards without it start with player 1
+    var current_player = boards[idx * 420u + 406u];
+    if (current_player < 1 || current_player > 4) {
+        current_player = 1;
+    }
     rng_state = params.seed + idx * 719393u;
     results[idx].score = blokus_random_rollout(idx, current_player);
 }
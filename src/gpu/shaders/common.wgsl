@@ -4,6 +4,9 @@ struct SimulationParams {
     current_player: i32,
     use_heuristic: u32,
     seed: u32,
+    near_win_weight: f32,
+    threat_weight: f32,
+    build_weight: f32,
 }
 
 struct SimulationResult {
@@ -330,8 +330,8 @@ fn evaluate_grid_game_common(idx: u32, game_type: u32) {
         let opp_threats = count_pattern(idx, -current_player, line_size - 1);
         let opp_builds = count_pattern(idx, -current_player, line_size - 2);
         
-        let player_score = f32(player_near_wins) * 100.0 + f32(player_threats) * 10.0 + f32(player_builds) * 1.0;
-        let opp_score = f32(opp_near_wins) * 100.0 + f32(opp_threats) * 10.0 + f32(opp_builds) * 1.0;
+        let player_score = f32(player_near_wins) * params.near_win_weight + f32(player_threats) * params.threat_weight + f32(player_builds) * params.build_weight;
+        let opp_score = f32(opp_near_wins) * params.near_win_weight + f32(opp_threats) * params.threat_weight + f32(opp_builds) * params.build_weight;
         
         results[idx].score = player_score - opp_score;
     } else {
@@ -23,6 +23,7 @@ use crate::games::gomoku::GomokuState;
 use crate::games::hive::HiveState;
 use crate::games::othello::OthelloState;
 use mcts::{GameState, MCTS, SearchStatistics};
+use mcts::presets::PresetConfig;
 
 use super::game_renderers::{GameRenderer, create_renderer_for_game};
 
@@ -150,6 +151,7 @@ impl AIWorker {
         shared_tree: bool,
         gpu_threads: usize,
         gpu_use_heuristic: bool,
+        preset: Option<PresetConfig>,
     ) -> Self {
         use std::sync::mpsc::channel;
         use std::collections::HashMap;
@@ -162,6 +164,12 @@ impl AIWorker {
         let handle = std::thread::spawn(move || {
             let mut mcts_cpu_map: HashMap<i32, MCTS<GameWrapper>> = HashMap::new();
             let mut mcts_gpu_map: HashMap<i32, MCTS<GameWrapper>> = HashMap::new();
+            let with_preset = |mut mcts: MCTS<GameWrapper>| {
+                if let Some(config) = &preset {
+                    config.apply(&mut mcts);
+                }
+                mcts
+            };
 
             for request in rx_req {
                 match request {
@@ -175,7 +183,7 @@ impl AIWorker {
                         let mcts_opt = match player_type {
                             PlayerType::AiCpu => {
                                 Some(mcts_cpu_map.entry(key).or_insert_with(|| {
-                                    MCTS::new(cpu_exploration_constant, num_threads, max_nodes)
+                                    with_preset(MCTS::new(cpu_exploration_constant, num_threads, max_nodes))
                                 }))
                             },
                             PlayerType::AiGpu => {
@@ -190,7 +198,7 @@ impl AIWorker {
                                         if let Some(msg) = gpu_msg {
                                             eprintln!("[AI] {}", msg);
                                         }
-                                        mcts_gpu_map.insert(key, new_mcts);
+                                        mcts_gpu_map.insert(key, with_preset(new_mcts));
                                     }
                                     mcts_gpu_map.get_mut(&key)
                                 }
@@ -198,7 +206,7 @@ impl AIWorker {
                                 {
                                     eprintln!("[AI] GPU not available, falling back to CPU");
                                     Some(mcts_cpu_map.entry(key).or_insert_with(|| {
-                                        MCTS::new(cpu_exploration_constant, num_threads, max_nodes)
+                                        with_preset(MCTS::new(cpu_exploration_constant, num_threads, max_nodes))
                                     }))
                                 }
                             },
@@ -315,6 +323,8 @@ pub struct GuiApp {
     pub ai_only: bool,
     pub shared_tree: bool,
     pub gpu_use_heuristic: bool,
+    /// AI personality applied to every engine the worker creates
+    pub preset: Option<PresetConfig>,
     pub selected_settings_index: usize,
 
     // UI state
@@ -358,6 +368,7 @@ impl GuiApp {
         timeout_secs: u64,
         stats_interval_secs: u64,
         ai_only: bool,
+        preset: Option<PresetConfig>,
     ) -> Self {
         let default_game = GameWrapper::Gomoku(GomokuState::new(board_size, line_size));
         let game_controller = GameController::new(default_game.clone());
@@ -375,7 +386,7 @@ impl GuiApp {
             game_status: GameStatus::InProgress,
             move_history: Vec::new(),
             game_renderer: renderer,
-            ai_worker: AIWorker::new(cpu_exploration_constant, gpu_exploration_constant, num_threads, max_nodes, search_iterations, shared_tree, gpu_threads, gpu_use_heuristic),
+            ai_worker: AIWorker::new(cpu_exploration_constant, gpu_exploration_constant, num_threads, max_nodes, search_iterations, shared_tree, gpu_threads, gpu_use_heuristic, preset),
             ai_thinking: false,
             ai_thinking_start: None,
             last_search_stats: None,
@@ -392,6 +403,7 @@ impl GuiApp {
             ai_only,
             shared_tree,
             gpu_use_heuristic,
+            preset,
             selected_settings_index: 0,
             needs_redraw: true,
             hover_button: None,
@@ -454,6 +466,7 @@ impl GuiApp {
             self.shared_tree,
             self.gpu_threads,
             self.gpu_use_heuristic,
+            self.preset,
         );
 
         // Check if AI should move first
@@ -462,7 +475,8 @@ impl GuiApp {
 
     /// Check if it's AI's turn and start search if needed
     pub fn check_ai_turn(&mut self) {
-        if self.game_status != GameStatus::InProgress {
+        // The AI waits while an earlier position is being reviewed
+        if self.game_status != GameStatus::InProgress || self.game_controller.is_reviewing() {
             return;
         }
 
@@ -536,10 +550,34 @@ impl GuiApp {
         }
     }
 
+    /// Show the position after the first `index` moves
+    ///
+    /// Jumping past the last move returns to the live position and lets the AI
+    /// continue if it is its turn.
+    pub fn review_goto(&mut self, index: usize) {
+        self.game_controller.goto_move(index);
+        self.game = self.game_controller.get_state_for_search();
+        self.needs_redraw = true;
+        self.check_ai_turn();
+    }
+
+    /// Step one move back in the game history
+    pub fn review_previous(&mut self) {
+        let shown = self.game_controller.review_index().unwrap_or(self.game_controller.move_count());
+        self.review_goto(shown.saturating_sub(1));
+    }
+
+    /// Step one move forward in the game history
+    pub fn review_next(&mut self) {
+        if let Some(shown) = self.game_controller.review_index() {
+            self.review_goto(shown + 1);
+        }
+    }
+
     /// Update application state (called periodically)
     pub fn update(&mut self) {
-        // Check for AI response
-        if self.ai_thinking {
+        // Check for AI response; a finished search stays queued while reviewing
+        if self.ai_thinking && !self.game_controller.is_reviewing() {
             if let Some(AIResponse::BestMove(mv, stats)) = self.ai_worker.try_recv() {
                 self.last_search_stats = Some(stats);
                 
@@ -808,7 +846,7 @@ impl GuiApp {
     /// Get formatted debug stats for display
     /// 
     /// Returns formatted lines that fit within typical panel widths.
-    /// Long move strings are shortened for better display.
+    /// Moves are shown in each game's `format_move` notation.
     pub fn get_debug_stats_lines(&self) -> Vec<String> {
         let mut lines = vec!["Debug Statistics".to_string(), String::new()];
         
@@ -826,9 +864,7 @@ impl GuiApp {
             
             lines.push("Top AI Moves:".to_string());
             for (i, (move_str, (value, visits))) in sorted_children.iter().take(10).enumerate() {
-                // Shorten the move string for display - extract just the move part
-                let short_move = shorten_move_string(move_str);
-                lines.push(format!("{}. {}", i + 1, short_move));
+                lines.push(format!("{}. {}", i + 1, move_str));
                 lines.push(format!("   {:.1} ({} visits)", value, visits));
             }
         } else {
@@ -856,38 +892,3 @@ impl GuiApp {
         self.game_controller.format_history_for_clipboard()
     }
 }
-
-/// Shorten a move string for display in the debug panel
-/// 
-/// Extracts the essential move information from verbose move strings like
-/// "Gomoku(GomokuMove(3, 3))" -> "G(3,3)"
-fn shorten_move_string(move_str: &str) -> String {
-    // Try to extract coordinates from patterns like "Move(x, y)" or "(x, y)"
-    if let Some(start) = move_str.find('(') {
-        if let Some(end) = move_str.rfind(')') {
-            // Get game prefix (first letter)
-            let prefix = move_str.chars().next().unwrap_or('?');
-            // Get the inner content
-            let inner = &move_str[start..=end];
-            // Try to simplify nested parens like "GomokuMove(3, 3)" -> "(3,3)"
-            if let Some(inner_start) = inner.find('(') {
-                if inner_start > 0 {
-                    // There's nested content, extract innermost
-                    let coords = &inner[inner_start..];
-                    // Remove spaces for compactness
-                    let compact = coords.replace(" ", "");
-                    return format!("{}{}", prefix, compact);
-                }
-            }
-            // Just use the parenthetical part
-            let compact = inner.replace(" ", "");
-            return format!("{}{}", prefix, compact);
-        }
-    }
-    // Fallback: truncate long strings
-    if move_str.len() > 25 {
-        format!("{}...", &move_str[..22])
-    } else {
-        move_str.to_string()
-    }
-}
@@ -21,7 +21,7 @@ use windows::{
             WM_RBUTTONDOWN, WM_RBUTTONUP,
             SetTimer, KillTimer, SetCursor,
         },
-        UI::Input::KeyboardAndMouse::{VK_ESCAPE, VK_RETURN, VK_UP, VK_DOWN, VK_LEFT, VK_RIGHT, VK_TAB, VK_SPACE, VK_BACK, VK_PRIOR, VK_NEXT, SetCapture, ReleaseCapture},
+        UI::Input::KeyboardAndMouse::{VK_ESCAPE, VK_RETURN, VK_UP, VK_DOWN, VK_LEFT, VK_RIGHT, VK_TAB, VK_SPACE, VK_BACK, VK_PRIOR, VK_NEXT, VK_HOME, VK_END, SetCapture, ReleaseCapture},
     },
     core::PCWSTR,
 };
@@ -555,6 +555,8 @@ fn handle_key(app: &mut GuiApp, vk: u16, hwnd: HWND) -> bool {
                 app.toggle_tab();
             } else if vk == 0x43 { // 'C' key - Copy move history to clipboard
                 app.copy_history_to_clipboard();
+            } else if handle_review_key(app, vk) {
+                // History review navigation
             } else if vk == VK_PRIOR.0 { // Page Up
                 match app.active_tab {
                     ActiveTab::DebugStats => app.scroll_debug_up(),
@@ -575,7 +577,7 @@ fn handle_key(app: &mut GuiApp, vk: u16, hwnd: HWND) -> bool {
                     .map(|(_, pt)| *pt == PlayerType::Human)
                     .unwrap_or(false);
 
-                if is_human && !app.ai_thinking {
+                if is_human && !app.ai_thinking && !app.game_controller.is_reviewing() {
                     let board_area = get_board_area(app, width, height);
                     let input = GameInput::Key { code: vk as u32, pressed: true };
                     
@@ -594,6 +596,8 @@ fn handle_key(app: &mut GuiApp, vk: u16, hwnd: HWND) -> bool {
                 app.go_back();
             } else if vk == 0x43 { // 'C' key - Copy move history to clipboard
                 app.copy_history_to_clipboard();
+            } else {
+                handle_review_key(app, vk);
             }
         }
         GuiMode::HowToPlay => {
@@ -622,6 +626,26 @@ fn handle_key(app: &mut GuiApp, vk: u16, hwnd: HWND) -> bool {
     false
 }
 
+/// Handle the move review keys
+///
+/// ',' and '.' step back and forward through the history, Home jumps to the
+/// initial position and End returns to the live position.
+/// Returns true if the key was consumed
+fn handle_review_key(app: &mut GuiApp, vk: u16) -> bool {
+    if vk == 0xBC { // ',' key
+        app.review_previous();
+    } else if vk == 0xBE { // '.' key
+        app.review_next();
+    } else if vk == VK_HOME.0 {
+        app.review_goto(0);
+    } else if vk == VK_END.0 {
+        app.review_goto(usize::MAX);
+    } else {
+        return false;
+    }
+    true
+}
+
 /// Handle mouse click
 /// Handle mouse click
 /// Returns (needs_redraw, start_capture) tuple
@@ -720,7 +744,7 @@ fn handle_click(app: &mut GuiApp, x: f32, y: f32, width: f32, height: f32) -> (b
                 return (true, false);
             }
 
-            if is_human && !app.ai_thinking {
+            if is_human && !app.ai_thinking && !app.game_controller.is_reviewing() {
                 // Use the same area for input hit-testing as we use for rendering.
                 // Rendering uses `board_area` (left of the splitter); using the full
                 // `game_area` here inflates offsets and misaligns clicks/hover.
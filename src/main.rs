@@ -207,6 +207,14 @@ struct Args {
     /// Random rollouts are slower but work for any game.
     #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
     gpu_use_heuristic: bool,
+
+    /// Named AI personality that overrides the CPU search parameters.
+    ///
+    /// Valid options: "balanced", "aggressive", "defensive", "fast"
+    /// Sets the CPU exploration factor and the iteration budget from the preset,
+    /// and applies its contempt and move selection to every AI engine.
+    #[arg(long)]
+    preset: Option<mcts::presets::Preset>,
 }
 
 /// Main entry point for the Parallel Multi-Game MCTS Engine
@@ -301,6 +309,13 @@ fn main() -> io::Result<()> {
         }
     }
 
+    // Apply the chosen AI personality on top of the individual settings
+    let preset_config = args.preset.map(|preset| preset.config());
+    if let Some(config) = &preset_config {
+        args.cpu_exploration_factor = config.exploration_parameter;
+        args.search_iterations = config.iterations as u32;
+    }
+
     // Ensure we have at least one thread for AI computation
     // Zero threads would cause deadlock in the thread pool
     let num_threads = if args.num_threads > 0 {
@@ -327,6 +342,7 @@ fn main() -> io::Result<()> {
             args.timeout_secs,
             args.stats_interval_secs,
             args.ai_only,
+            preset_config,
         );
         
         return gui::run_gui(gui_app)
@@ -0,0 +1,70 @@
+//! # Perft
+//!
+//! Counts the positions reachable in an exact number of moves, like chess perft.
+//!
+//! Comparing these counts against known reference values is a quick way to catch
+//! move-generation bugs when implementing a new game. Only the `GameState`
+//! interface is used.
+//!
+//! ## Example Usage
+//! ```rust
+//! use mcts::perft::perft;
+//! use mcts::games::connect4::Connect4State;
+//!
+//! assert_eq!(perft(&Connect4State::new(7, 6, 4), 2), 49);
+//! ```
+
+use crate::GameState;
+
+/// Counts the leaf positions exactly `depth` moves below `state`
+///
+/// Games that end before `depth` contribute nothing, and `depth` 0 counts the
+/// position itself.
+///
+/// # Arguments
+/// * `state` - Starting position
+/// * `depth` - Number of moves to enumerate
+pub fn perft<S: GameState>(state: &S, depth: usize) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    if state.is_terminal() {
+        return 0;
+    }
+
+    let moves = state.get_possible_moves();
+    if depth == 1 {
+        return moves.len() as u64;
+    }
+    moves
+        .iter()
+        .map(|mv| {
+            let mut next = state.clone();
+            next.make_move(mv);
+            perft(&next, depth - 1)
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::connect4::{Connect4Move, Connect4State};
+
+    #[test]
+    fn test_connect4_perft_reference_counts() {
+        let game = Connect4State::new(7, 6, 4);
+        assert_eq!(perft(&game, 0), 1);
+        assert_eq!(perft(&game, 1), 7);
+        // No game can end within the first 6 plies
+        assert_eq!(perft(&game, 4), 2401);
+    }
+
+    #[test]
+    fn test_connect4_perft_skips_full_columns() {
+        // Fill the first column
+        let moves = vec![Connect4Move(0); 6];
+        let game = Connect4State::from_moves(Connect4State::new(7, 6, 4), &moves).unwrap();
+        assert_eq!(perft(&game, 1), 6);
+    }
+}
@@ -0,0 +1,157 @@
+//! # Engine Presets
+//!
+//! Named bundles of search parameters ("personalities") for users who don't want
+//! to tune exploration, iterations, contempt and move selection by hand.
+//!
+//! ## Example Usage
+//! ```rust
+//! use mcts::presets::Preset;
+//! use mcts::games::connect4::Connect4State;
+//!
+//! let game_state = Connect4State::new(7, 6, 4);
+//! let mut mcts = Preset::Aggressive.build::<Connect4State>(4, 100000);
+//! let iterations = Preset::Aggressive.config().iterations;
+//! let (best_move, stats) = mcts.search(&game_state, iterations.min(1000), 0, 0);
+//! ```
+
+use crate::{GameState, MCTS, MoveSelectionStrategy};
+use std::fmt;
+use std::str::FromStr;
+
+/// A named engine personality
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Preset {
+    /// Sensible defaults for most games
+    Balanced,
+    /// Plays for the win: picks the highest-value move and avoids draws
+    Aggressive,
+    /// Plays it safe: picks the most explored move and leans towards draws
+    Defensive,
+    /// Low iteration budget for quick responses
+    Fast,
+}
+
+/// Search parameters bundled by a preset
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PresetConfig {
+    /// Exploration constant for the PUCT formula
+    pub exploration_parameter: f64,
+    /// Suggested number of iterations per search
+    pub iterations: i32,
+    /// Contempt applied to drawn rollouts
+    pub contempt: f64,
+    /// How the final move is picked
+    pub move_selection: MoveSelectionStrategy,
+}
+
+impl Preset {
+    /// All available presets
+    pub const ALL: [Preset; 4] = [Preset::Balanced, Preset::Aggressive, Preset::Defensive, Preset::Fast];
+
+    /// Returns the parameters of this preset
+    pub fn config(&self) -> PresetConfig {
+        match self {
+            Preset::Balanced => PresetConfig {
+                exploration_parameter: 1.4,
+                iterations: 1_000_000,
+                contempt: 0.0,
+                move_selection: MoveSelectionStrategy::MaxVisits,
+            },
+            Preset::Aggressive => PresetConfig {
+                exploration_parameter: 2.0,
+                iterations: 1_000_000,
+                contempt: 0.2,
+                move_selection: MoveSelectionStrategy::MaxQ,
+            },
+            Preset::Defensive => PresetConfig {
+                exploration_parameter: 1.0,
+                iterations: 1_000_000,
+                contempt: -0.1,
+                move_selection: MoveSelectionStrategy::MaxVisits,
+            },
+            Preset::Fast => PresetConfig {
+                exploration_parameter: 1.4,
+                iterations: 50_000,
+                contempt: 0.0,
+                move_selection: MoveSelectionStrategy::MaxVisits,
+            },
+        }
+    }
+
+    /// Creates an engine configured with this preset
+    ///
+    /// # Arguments
+    /// * `num_threads` - The number of threads to use for the search. If 0, rayon will use the default.
+    /// * `max_nodes` - Maximum number of nodes allowed in the tree.
+    pub fn build<S: GameState>(&self, num_threads: usize, max_nodes: usize) -> MCTS<S> {
+        let config = self.config();
+        let mut mcts = MCTS::new(config.exploration_parameter, num_threads, max_nodes);
+        config.apply(&mut mcts);
+        mcts
+    }
+}
+
+impl PresetConfig {
+    /// Applies the tree-independent settings of this config to an existing engine
+    ///
+    /// The exploration parameter is fixed at construction and the iteration count
+    /// is passed per search, so neither is changed here.
+    pub fn apply<S: GameState>(&self, mcts: &mut MCTS<S>) {
+        mcts.set_contempt(self.contempt);
+        mcts.set_move_selection_strategy(self.move_selection);
+    }
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Preset::Balanced => "balanced",
+            Preset::Aggressive => "aggressive",
+            Preset::Defensive => "defensive",
+            Preset::Fast => "fast",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Preset {
+    type Err = String;
+
+    /// Parses a preset name (case-insensitive)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Preset::ALL
+            .into_iter()
+            .find(|p| p.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("Unknown preset '{}'. Expected one of: balanced, aggressive, defensive, fast", s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::connect4::Connect4State;
+
+    #[test]
+    fn test_presets_return_legal_moves() {
+        let game = Connect4State::new(7, 6, 4);
+        for preset in Preset::ALL {
+            let mut mcts = preset.build::<Connect4State>(2, 100000);
+            let iterations = preset.config().iterations.min(500);
+            let (best_move, _) = mcts.search(&game, iterations, 0, 0);
+            assert!(game.is_legal(&best_move), "{} returned an illegal move", preset);
+        }
+    }
+
+    #[test]
+    fn test_preset_from_str() {
+        assert_eq!("Aggressive".parse::<Preset>(), Ok(Preset::Aggressive));
+        assert!("reckless".parse::<Preset>().is_err());
+    }
+
+    #[test]
+    fn test_defensive_prefers_draws_more_than_balanced() {
+        assert!(Preset::Defensive.config().contempt < Preset::Balanced.config().contempt);
+        let mcts = Preset::Defensive.build::<Connect4State>(1, 1000);
+        assert_eq!(mcts.get_contempt(), -0.1);
+    }
+}
@@ -0,0 +1,172 @@
+//! # Proof-Number Search
+//!
+//! A small exact solver for endgames, used through `MCTS::prove`.
+//!
+//! Proof-number search grows an AND/OR tree towards the cheapest-looking proof
+//! or disproof, which suits positions with high branching and narrow forcing
+//! lines better than fixed-depth search. It only uses the `GameState` interface.
+//!
+//! The outcome is decided with up to two binary searches sharing one node budget:
+//! first whether the side to move can force a win, then whether it can at least
+//! avoid losing.
+
+use crate::GameState;
+
+/// Exact game-theoretic value of a position for the side to move
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProofResult {
+    /// The side to move can force a win
+    Win,
+    /// Neither side can force a win
+    Draw,
+    /// The opponent can force a win
+    Loss,
+}
+
+/// Proof or disproof number of a node that can never be (dis)proved
+const INFINITY: u64 = u64::MAX;
+
+/// What the attacking player is trying to prove
+#[derive(Clone, Copy)]
+enum Goal {
+    /// The attacker wins
+    Win,
+    /// The game ends without the attacker losing
+    NoLoss,
+}
+
+impl Goal {
+    fn reached(self, winner: Option<i32>, attacker: i32) -> bool {
+        match self {
+            Goal::Win => winner == Some(attacker),
+            Goal::NoLoss => winner.is_none() || winner == Some(attacker),
+        }
+    }
+}
+
+struct ProofNode<S> {
+    state: S,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    /// True where the attacker is to move (OR node)
+    attacker_to_move: bool,
+    proof: u64,
+    disproof: u64,
+}
+
+/// Solves `state` within `node_limit` nodes
+///
+/// # Returns
+/// The value for the side to move, or None if the budget ran out first
+pub(crate) fn prove<S: GameState>(state: &S, node_limit: usize) -> Option<ProofResult> {
+    let mut budget = node_limit;
+    if search(state, Goal::Win, &mut budget)? {
+        return Some(ProofResult::Win);
+    }
+    if search(state, Goal::NoLoss, &mut budget)? {
+        Some(ProofResult::Draw)
+    } else {
+        Some(ProofResult::Loss)
+    }
+}
+
+/// Runs one binary proof-number search for the side to move of `root_state`
+///
+/// Every created node is taken from `budget`. Returns None when it runs out.
+fn search<S: GameState>(root_state: &S, goal: Goal, budget: &mut usize) -> Option<bool> {
+    let attacker = root_state.get_current_player();
+    let mut nodes = Vec::new();
+    nodes.push(new_node(root_state.clone(), None, attacker, goal));
+    *budget = budget.checked_sub(1)?;
+
+    while nodes[0].proof != 0 && nodes[0].disproof != 0 {
+        // Descend to the most-proving node
+        let mut current = 0;
+        while !nodes[current].children.is_empty() {
+            let node = &nodes[current];
+            current = if node.attacker_to_move {
+                *node.children.iter().min_by_key(|&&c| nodes[c].proof)?
+            } else {
+                *node.children.iter().min_by_key(|&&c| nodes[c].disproof)?
+            };
+        }
+
+        // Expand it
+        let moves = nodes[current].state.get_possible_moves();
+        *budget = budget.checked_sub(moves.len())?;
+        for mv in moves {
+            let mut child_state = nodes[current].state.clone();
+            child_state.make_move(&mv);
+            let index = nodes.len();
+            nodes.push(new_node(child_state, Some(current), attacker, goal));
+            nodes[current].children.push(index);
+        }
+
+        // Back up proof and disproof numbers to the root
+        let mut ancestor = Some(current);
+        while let Some(index) = ancestor {
+            let (proof, disproof) = combine(&nodes, index);
+            nodes[index].proof = proof;
+            nodes[index].disproof = disproof;
+            ancestor = nodes[index].parent;
+        }
+    }
+
+    Some(nodes[0].proof == 0)
+}
+
+/// Creates a node, scoring it immediately if the game is over there
+fn new_node<S: GameState>(state: S, parent: Option<usize>, attacker: i32, goal: Goal) -> ProofNode<S> {
+    let attacker_to_move = state.get_current_player() == attacker;
+    let (proof, disproof) = if state.is_terminal() || state.get_possible_moves().is_empty() {
+        if goal.reached(state.get_winner(), attacker) { (0, INFINITY) } else { (INFINITY, 0) }
+    } else {
+        (1, 1)
+    };
+    ProofNode { state, parent, children: Vec::new(), attacker_to_move, proof, disproof }
+}
+
+/// Proof and disproof numbers of an expanded node from its children
+fn combine<S>(nodes: &[ProofNode<S>], index: usize) -> (u64, u64) {
+    let node = &nodes[index];
+    let children = node.children.iter().map(|&c| &nodes[c]);
+    let sum = |values: &mut dyn Iterator<Item = u64>| values.fold(0u64, u64::saturating_add);
+    if node.attacker_to_move {
+        let proof = children.clone().map(|c| c.proof).min().unwrap_or(INFINITY);
+        (proof, sum(&mut children.map(|c| c.disproof)))
+    } else {
+        let disproof = children.clone().map(|c| c.disproof).min().unwrap_or(INFINITY);
+        (sum(&mut children.map(|c| c.proof)), disproof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MCTS;
+    use crate::games::connect4::{Connect4Move, Connect4State};
+
+    fn position(columns: &[usize]) -> Connect4State {
+        let moves: Vec<Connect4Move> = columns.iter().map(|&c| Connect4Move(c)).collect();
+        Connect4State::from_moves(Connect4State::new(7, 6, 4), &moves).unwrap()
+    }
+
+    #[test]
+    fn test_proves_connect4_open_three() {
+        let mcts = MCTS::<Connect4State>::new(1.4, 1, 1000);
+
+        // X can complete an open three on the bottom row
+        let attacker = position(&[1, 6, 2, 6]);
+        assert_eq!(mcts.prove(&attacker, 100_000), Some(ProofResult::Win));
+
+        // O to move can block only one end of X's open three
+        let defender = position(&[1, 6, 2, 6, 3]);
+        assert_eq!(mcts.prove(&defender, 100_000), Some(ProofResult::Loss));
+    }
+
+    #[test]
+    fn test_prove_gives_up_when_budget_is_exhausted() {
+        let mcts = MCTS::<Connect4State>::new(1.4, 1, 1000);
+        assert_eq!(mcts.prove(&Connect4State::new(7, 6, 4), 50), None);
+    }
+}
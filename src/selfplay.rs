@@ -0,0 +1,236 @@
+//! # Self-Play Matches
+//!
+//! Plays two differently-configured engines against each other to measure whether
+//! a parameter change actually makes the engine stronger, and streams search
+//! policies out of self-play games for training data.
+//!
+//! ## Example Usage
+//! ```rust
+//! use mcts::presets::Preset;
+//! use mcts::selfplay::match_configs;
+//! use mcts::games::connect4::Connect4State;
+//!
+//! let game = Connect4State::new(7, 6, 4);
+//! let result = match_configs(&Preset::Aggressive.config(), &Preset::Defensive.config(), 2, 50, &game);
+//! println!("{}", result);
+//! ```
+
+use crate::presets::PresetConfig;
+use crate::{GameState, MCTS};
+use std::fmt;
+
+/// Node budget of each engine taking part in a match
+const MATCH_MAX_NODES: usize = 1_000_000;
+
+/// z-score of the reported Elo confidence interval (95%)
+const ELO_CONFIDENCE_Z: f64 = 1.96;
+
+/// Outcome of a match between two engine configurations, from A's point of view
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MatchResult {
+    /// Games won by configuration A
+    pub wins: usize,
+    /// Games won by configuration B
+    pub losses: usize,
+    /// Drawn games
+    pub draws: usize,
+    /// Estimated Elo difference of A over B
+    pub elo: f64,
+    /// 95% confidence interval of the Elo difference as (lower, upper)
+    pub elo_interval: (f64, f64),
+}
+
+impl MatchResult {
+    /// Builds a result from game counts and computes the Elo estimate
+    pub fn from_counts(wins: usize, losses: usize, draws: usize) -> Self {
+        let games = (wins + losses + draws) as f64;
+        if games == 0.0 {
+            return Self { wins, losses, draws, elo: 0.0, elo_interval: (0.0, 0.0) };
+        }
+
+        let score = (wins as f64 + 0.5 * draws as f64) / games;
+        let variance = (wins as f64 * (1.0 - score).powi(2)
+            + losses as f64 * score.powi(2)
+            + draws as f64 * (0.5 - score).powi(2))
+            / games;
+        let margin = ELO_CONFIDENCE_Z * (variance / games).sqrt();
+
+        // Clamp to half a game from a clean sweep so the estimate stays finite
+        let clamp = |s: f64| s.clamp(0.5 / games, 1.0 - 0.5 / games);
+        Self {
+            wins,
+            losses,
+            draws,
+            elo: elo_from_score(clamp(score)),
+            elo_interval: (elo_from_score(clamp(score - margin)), elo_from_score(clamp(score + margin))),
+        }
+    }
+
+    /// Total number of games played
+    pub fn games(&self) -> usize {
+        self.wins + self.losses + self.draws
+    }
+}
+
+impl fmt::Display for MatchResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "+{} -{} ={} (Elo {:+.0}, 95% CI [{:+.0}, {:+.0}])",
+            self.wins, self.losses, self.draws, self.elo, self.elo_interval.0, self.elo_interval.1
+        )
+    }
+}
+
+/// Converts an expected score in (0, 1) to an Elo difference
+fn elo_from_score(score: f64) -> f64 {
+    -400.0 * (1.0 / score - 1.0).log10()
+}
+
+/// Plays a balanced match between two engine configurations
+///
+/// Colors alternate every game: A moves first in even-numbered games and B in
+/// odd-numbered ones. Intended for two-player games.
+///
+/// # Arguments
+/// * `config_a` - Configuration of the engine being evaluated
+/// * `config_b` - Configuration of the reference engine
+/// * `games` - Number of games to play
+/// * `budget` - Iterations per move
+/// * `game` - Starting position of every game
+pub fn match_configs<S: GameState>(
+    config_a: &PresetConfig,
+    config_b: &PresetConfig,
+    games: usize,
+    budget: i32,
+    game: &S,
+) -> MatchResult {
+    let first_player = game.get_current_player();
+    let (mut wins, mut losses, mut draws) = (0, 0, 0);
+
+    for game_index in 0..games {
+        let a_moves_first = game_index % 2 == 0;
+        let mut engine_a = new_engine::<S>(config_a);
+        let mut engine_b = new_engine::<S>(config_b);
+        let mut state = game.clone();
+
+        while !state.is_terminal() {
+            let a_to_move = (state.get_current_player() == first_player) == a_moves_first;
+            let engine = if a_to_move { &mut engine_a } else { &mut engine_b };
+            let (mv, _) = engine.search(&state, budget, 0, 0);
+            state.make_move(&mv);
+            engine_a.advance_root(&mv);
+            engine_b.advance_root(&mv);
+        }
+
+        match state.get_winner() {
+            Some(winner) if (winner == first_player) == a_moves_first => wins += 1,
+            Some(_) => losses += 1,
+            None => draws += 1,
+        }
+    }
+
+    MatchResult::from_counts(wins, losses, draws)
+}
+
+/// Search policy at a position reached in self-play
+#[derive(Debug, Clone)]
+pub struct PolicySample<S: GameState> {
+    /// Position before the move
+    pub state: S,
+    /// Visit share of each root move, most visited first
+    pub policy: Vec<(S::Move, f64)>,
+    /// Move that was played
+    pub chosen: S::Move,
+}
+
+/// A self-play game that yields one policy sample per move
+///
+/// Each call to `next_sample` searches the current position, records the root
+/// policy, plays the chosen move and reuses the subtree below it, so samples
+/// can be written out as the game progresses instead of collected first.
+/// The session also iterates over its samples.
+pub struct SelfPlaySession<S: GameState> {
+    mcts: MCTS<S>,
+    state: S,
+    iterations: i32,
+}
+
+impl<S: GameState> SelfPlaySession<S> {
+    /// Starts a session from `state`
+    ///
+    /// # Arguments
+    /// * `mcts` - Engine playing both sides
+    /// * `state` - Starting position
+    /// * `iterations` - Iterations per move
+    pub fn new(mcts: MCTS<S>, state: S, iterations: i32) -> Self {
+        Self { mcts, state, iterations }
+    }
+
+    /// Current position of the game
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    /// Plays one move and returns the policy searched before it
+    ///
+    /// # Returns
+    /// The sample, or None once the game is over
+    pub fn next_sample(&mut self) -> Option<PolicySample<S>> {
+        if self.state.is_terminal() {
+            return None;
+        }
+
+        let (chosen, _) = self.mcts.search(&self.state, self.iterations, 0, 0);
+        let children = self.mcts.get_root_children_stats();
+        let total = children.values().map(|&(_, visits)| visits).sum::<i32>().max(1) as f64;
+        let mut policy: Vec<_> = children
+            .into_iter()
+            .map(|(mv, (_, visits))| (mv, visits as f64 / total))
+            .collect();
+        policy.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        let state = self.state.clone();
+        self.state.make_move(&chosen);
+        self.mcts.advance_root(&chosen);
+        Some(PolicySample { state, policy, chosen })
+    }
+}
+
+impl<S: GameState> Iterator for SelfPlaySession<S> {
+    type Item = PolicySample<S>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_sample()
+    }
+}
+
+/// Creates an engine for one side of a match
+fn new_engine<S: GameState>(config: &PresetConfig) -> MCTS<S> {
+    let mut mcts = MCTS::new(config.exploration_parameter, 0, MATCH_MAX_NODES);
+    config.apply(&mut mcts);
+    mcts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::connect4::Connect4State;
+    use crate::presets::Preset;
+
+    #[test]
+    fn test_match_configs_counts_and_elo() {
+        let game = Connect4State::new(7, 6, 4);
+        let result = match_configs(&Preset::Aggressive.config(), &Preset::Defensive.config(), 4, 50, &game);
+        assert_eq!(result.games(), 4);
+        assert!(result.elo.is_finite());
+        assert!(result.elo_interval.0 <= result.elo && result.elo <= result.elo_interval.1);
+    }
+
+    #[test]
+    fn test_elo_from_counts() {
+        assert_eq!(MatchResult::from_counts(5, 5, 0).elo, 0.0);
+        let sweep = MatchResult::from_counts(10, 0, 0);
+        assert!(sweep.elo.is_finite() && sweep.elo > 0.0);
+    }
+}
@@ -0,0 +1,99 @@
+//! # Tuning
+//!
+//! Helpers for picking engine parameters by measurement instead of guesswork.
+//!
+//! ## Example Usage
+//! ```rust
+//! use mcts::tuning::{SearchBudget, suggest_thread_count};
+//! use mcts::games::connect4::Connect4State;
+//!
+//! let threads = suggest_thread_count(&Connect4State::new(7, 6, 4), SearchBudget::Iterations(500));
+//! assert!(threads >= 1);
+//! ```
+
+use crate::{GameState, MCTS};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Node limit of the throwaway engines used for benchmarking
+const BENCHMARK_MAX_NODES: usize = 200_000;
+
+/// How long each benchmark search runs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SearchBudget {
+    /// A fixed number of simulations
+    Iterations(i32),
+    /// A fixed amount of wall-clock time
+    Time(Duration),
+}
+
+/// Suggests the thread count with the best simulations per second for a game
+///
+/// Runs one short search from `sample` with a fresh engine at 1, 2, 4, ... threads
+/// up to the available parallelism, so it takes a few multiples of `budget`.
+///
+/// # Arguments
+/// * `sample` - A typical position of the game
+/// * `budget` - Length of each benchmark search
+///
+/// # Returns
+/// A thread count between 1 and the available parallelism
+pub fn suggest_thread_count<S: GameState>(sample: &S, budget: SearchBudget) -> usize {
+    let available = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let mut candidates: Vec<usize> = std::iter::successors(Some(1), |&n| Some(n * 2))
+        .take_while(|&n| n < available)
+        .collect();
+    candidates.push(available);
+
+    let mut best = (1, 0.0);
+    for threads in candidates {
+        let rate = simulations_per_second(sample, threads, budget);
+        if rate > best.1 {
+            best = (threads, rate);
+        }
+    }
+    best.0
+}
+
+/// Measures the search throughput of a fresh engine with `threads` threads
+fn simulations_per_second<S: GameState>(sample: &S, threads: usize, budget: SearchBudget) -> f64 {
+    let mut mcts = MCTS::<S>::new(1.4, threads, BENCHMARK_MAX_NODES);
+    let progress = mcts.progress_handle();
+    let start = Instant::now();
+    match budget {
+        SearchBudget::Iterations(iterations) => {
+            mcts.search(sample, iterations, 0, 0);
+        }
+        SearchBudget::Time(duration) => {
+            let stop = Arc::new(AtomicBool::new(false));
+            let timer = std::thread::spawn({
+                let stop = stop.clone();
+                move || {
+                    std::thread::sleep(duration);
+                    stop.store(true, Ordering::Relaxed);
+                }
+            });
+            mcts.search_with_stop(sample, i32::MAX, 0, 0, Some(stop));
+            let _ = timer.join();
+        }
+    }
+    let elapsed = start.elapsed().as_secs_f64();
+    if elapsed > 0.0 { progress.completed_iterations() as f64 / elapsed } else { 0.0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::connect4::Connect4State;
+
+    #[test]
+    fn test_suggestion_is_in_range() {
+        let available = std::thread::available_parallelism().map_or(1, |n| n.get());
+        let game = Connect4State::new(7, 6, 4);
+        for budget in [SearchBudget::Iterations(2000), SearchBudget::Time(Duration::from_millis(20))] {
+            let threads = suggest_thread_count(&game, budget);
+            assert!((1..=available).contains(&threads), "suggested {} threads", threads);
+        }
+    }
+}